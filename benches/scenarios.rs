@@ -0,0 +1,32 @@
+//! Benchmark of the solver over the built-in scenarios.
+//!
+//!     cargo bench -- --save baseline.csv
+//!     cargo bench -- --baseline baseline.csv
+//!
+//! takes the same options as the `bench` command.
+
+extern crate rgb_express_solver;
+
+use std::env;
+use std::process;
+use rgb_express_solver::bench;
+
+fn main() {
+    // cargo passes --bench to every benchmark.
+    let args = env::args().skip(1).filter(|arg| arg != "--bench");
+    let options = match bench::Options::from_args(args) {
+        Ok(options) => options,
+        Err(arg) => {
+            eprintln!("unexpected argument: {}", arg);
+            process::exit(1);
+        },
+    };
+    match bench::run(&options) {
+        Ok(0) => {},
+        Ok(_) => process::exit(1),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+    }
+}
@@ -0,0 +1,9 @@
+#![no_main]
+#[macro_use] extern crate libfuzzer_sys;
+extern crate rgb_express_solver;
+
+use rgb_express_solver::fuzz;
+
+fuzz_target!(|data: &[u8]| {
+    fuzz::simulate(data);
+});
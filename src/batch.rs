@@ -0,0 +1,206 @@
+use std::fmt;
+use std::fs;
+use std::path;
+use std::str;
+use std::time;
+use ::solver;
+use ::level;
+use ::report;
+use ::cache;
+
+/// What a level file says about itself, in an `expected:` header entry.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Expected {
+    Solved,
+    Unsolvable,
+    /// Solved in that many ticks.
+    Ticks(usize),
+}
+
+impl str::FromStr for Expected {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Expected, String> {
+        match s {
+            "solved" => Ok(Expected::Solved),
+            "unsolvable" => Ok(Expected::Unsolvable),
+            _ => s.parse().map(Expected::Ticks).map_err(|_| format!("unknown expected result `{}`", s)),
+        }
+    }
+}
+
+impl fmt::Display for Expected {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            Expected::Solved => write!(fmt, "solved"),
+            Expected::Unsolvable => write!(fmt, "unsolvable"),
+            Expected::Ticks(n) => write!(fmt, "{}", n),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Status {
+    Solved,
+    Unsolvable,
+    /// The budget ran out.
+    GaveUp,
+    /// The file could not be read.
+    Error(String),
+}
+
+impl Status {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Status::Solved     => "solved",
+            Status::Unsolvable => "unsolvable",
+            Status::GaveUp     => "gave up",
+            Status::Error(_)   => "error",
+        }
+    }
+}
+
+/// The result of solving one level file.
+pub struct Outcome {
+    pub file: String,
+    pub status: Status,
+    pub time: time::Duration,
+    pub nodes: usize,
+    /// Ticks of the solution found.
+    pub ticks: Option<usize>,
+    pub expected: Option<Expected>,
+    /// The solution came from the cache rather than a search.
+    pub cached: bool,
+}
+
+impl Outcome {
+    /// True if the level has an expected result which was not met.
+    pub fn mismatch(&self) -> bool {
+        match (self.expected, &self.status) {
+            (None, _) => false,
+            (Some(Expected::Solved), &Status::Solved) => false,
+            (Some(Expected::Unsolvable), &Status::Unsolvable) => false,
+            (Some(Expected::Ticks(n)), &Status::Solved) => self.ticks != Some(n),
+            _ => true,
+        }
+    }
+}
+
+/// Solve every level file of `dir`, in name order, exploring at most
+/// `budget` states for each. Solutions in `cache` are used instead of
+/// searching, and the ones found are added to it.
+pub fn solve_all(dir: &path::Path, budget: usize, cache: Option<&cache::Cache>) -> Result<Vec<Outcome>, String> {
+    Ok(level_files(dir)?.iter().map(|file| solve_file(file, budget, cache)).collect())
+}
+
+/// The files of `dir` not hidden, in name order.
+pub fn level_files(dir: &path::Path) -> Result<Vec<path::PathBuf>, String> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))? {
+        let entry = entry.map_err(|e| format!("{}: {}", dir.display(), e))?;
+        let hidden = entry.file_name().to_string_lossy().starts_with('.');
+        if !hidden && entry.file_type().is_ok_and(|t| t.is_file()) {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn solve_file(file: &path::Path, budget: usize, cache: Option<&cache::Cache>) -> Outcome {
+    let mut outcome = Outcome {
+        file: file.file_name().unwrap().to_string_lossy().into_owned(),
+        status: Status::GaveUp,
+        time: time::Duration::default(),
+        nodes: 0,
+        ticks: None,
+        expected: None,
+        cached: false,
+    };
+    let level = match level::Level::load(file) {
+        Ok(level) => level,
+        Err(e) => {
+            outcome.status = Status::Error(e);
+            return outcome;
+        },
+    };
+    if let Some((_, value)) = level.header.iter().find(|&(key, _)| key == "expected") {
+        match value.parse() {
+            Ok(expected) => outcome.expected = Some(expected),
+            Err(e) => {
+                outcome.status = Status::Error(format!("{}: {}", file.display(), e));
+                return outcome;
+            },
+        }
+    }
+
+    let start = time::Instant::now();
+    let strategy = solver::Strategy::DepthFirst;
+    if let Some(solution) = cache.and_then(|c| c.get(&level.map, &level.cars, strategy)) {
+        outcome.time = start.elapsed();
+        outcome.ticks = Some(solution.dirs.len());
+        outcome.status = Status::Solved;
+        outcome.cached = true;
+        return outcome;
+    }
+    let mut s = solver::Solver::new(level.map.clone(), level.cars.clone());
+    s.set_budget(budget);
+    s.set_strategy(strategy);
+    let solution = s.solve();
+    if let (Some(cache), Some(solution)) = (cache, solution.as_ref()) {
+        if let Err(e) = cache.put(&level.map, &level.cars, strategy, solution) {
+            eprintln!("{}", e);
+        }
+    }
+    outcome.time = start.elapsed();
+    outcome.nodes = s.nodes();
+    outcome.ticks = solution.as_ref().map(|solution| solution.dirs.len());
+    outcome.status = match solution {
+        Some(_) => Status::Solved,
+        None if s.exhausted() => Status::GaveUp,
+        None => Status::Unsolvable,
+    };
+    outcome
+}
+
+pub const CSV_HEADER: &str = "file,status,time_ms,nodes,ticks,expected,mismatch,cached";
+
+pub fn to_csv(outcomes: &[Outcome]) -> String {
+    let mut s = format!("{}\n", CSV_HEADER);
+    for o in outcomes {
+        s.push_str(&format!("{},{},{:.3},{},{},{},{},{}\n",
+                            csv_field(&o.file), o.status.name(), millis(o.time), o.nodes,
+                            o.ticks.map_or(String::new(), |n| n.to_string()),
+                            o.expected.map_or(String::new(), |e| e.to_string()),
+                            o.mismatch(), o.cached));
+    }
+    s
+}
+
+pub fn to_json(outcomes: &[Outcome]) -> String {
+    let outcomes: Vec<String> = outcomes.iter().map(|o| {
+        format!("{{\"file\":{},\"status\":\"{}\",{}\"time_ms\":{:.3},\"nodes\":{},\"ticks\":{},\"expected\":{},\"mismatch\":{},\"cached\":{}}}",
+                report::json_string(&o.file), o.status.name(),
+                match o.status {
+                    Status::Error(ref e) => format!("\"error\":{},", report::json_string(e)),
+                    _ => String::new(),
+                },
+                millis(o.time), o.nodes,
+                o.ticks.map_or("null".to_string(), |n| n.to_string()),
+                o.expected.map_or("null".to_string(), |e| report::json_string(&e.to_string())),
+                o.mismatch(), o.cached)
+    }).collect();
+    format!("[{}]\n", outcomes.join(","))
+}
+
+pub fn millis(d: time::Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time;
+use ::solver;
+use ::level;
+use ::scenario;
+use ::batch;
+
+/// Timings of repeated runs of the solver on one level.
+pub struct Measure {
+    pub level: String,
+    pub strategy: solver::Strategy,
+    /// Sorted from the fastest.
+    pub times: Vec<time::Duration>,
+    pub nodes: usize,
+    /// Most states held at once by the search, a count rather than bytes.
+    pub peak_states: usize,
+    pub solved: bool,
+}
+
+impl Measure {
+    /// Time under which `p` percent of the runs finished.
+    pub fn percentile(&self, p: usize) -> time::Duration {
+        let i = (self.times.len() * p).div_ceil(100).max(1) - 1;
+        self.times[i.min(self.times.len() - 1)]
+    }
+
+    pub fn median(&self) -> time::Duration {
+        self.percentile(50)
+    }
+}
+
+/// Solve `level` `runs` times with `strategy`, exploring at most `budget`
+/// states.
+pub fn measure(name: &str, level: &level::Level, strategy: solver::Strategy, runs: usize, budget: usize) -> Measure {
+    let mut m = Measure {
+        level: name.to_string(),
+        strategy,
+        times: vec![],
+        nodes: 0,
+        peak_states: 0,
+        solved: false,
+    };
+    for _ in 0..runs.max(1) {
+        let start = time::Instant::now();
+        let mut s = solver::Solver::new(level.map.clone(), level.cars.clone());
+        s.set_strategy(strategy);
+        s.set_budget(budget);
+        m.solved = s.solve().is_some();
+        m.times.push(start.elapsed());
+        m.nodes = s.nodes();
+        m.peak_states = s.peak();
+    }
+    m.times.sort();
+    m
+}
+
+/// Measures saved by an earlier run, by level and strategy name.
+pub struct Baseline {
+    entries: HashMap<(String, String), Entry>,
+}
+
+struct Entry {
+    median: f64,
+    nodes: usize,
+    peak_states: usize,
+}
+
+/// Differences of median time under this many milliseconds are noise.
+const NOISE: f64 = 0.5;
+
+pub const CSV_HEADER: &str = "level,strategy,median_ms,p90_ms,nodes,peak_states,solved";
+
+/// Write `measures` in the format read by `Baseline::from_csv`.
+pub fn to_csv(measures: &[Measure]) -> String {
+    let mut s = format!("{}\n", CSV_HEADER);
+    for m in measures {
+        s.push_str(&format!("{},{},{:.3},{:.3},{},{},{}\n",
+                            m.level, m.strategy.name(), batch::millis(m.median()), batch::millis(m.percentile(90)),
+                            m.nodes, m.peak_states, m.solved));
+    }
+    s
+}
+
+impl Baseline {
+    pub fn from_csv(s: &str) -> Result<Baseline, String> {
+        let mut entries = HashMap::new();
+        for (n, line) in s.lines().enumerate().skip(1) {
+            let fields: Vec<&str> = line.split(',').collect();
+            let entry = match fields[..] {
+                [level, strategy, median, _, nodes, peak_states, _] => match (median.parse(), nodes.parse(), peak_states.parse()) {
+                    (Ok(median), Ok(nodes), Ok(peak_states)) => ((level.to_string(), strategy.to_string()), Entry { median, nodes, peak_states }),
+                    _ => return Err(format!("line {}: invalid number", n + 1)),
+                },
+                _ => return Err(format!("line {}: expected {}", n + 1, CSV_HEADER)),
+            };
+            entries.insert(entry.0, entry.1);
+        }
+        Ok(Baseline { entries })
+    }
+
+    /// Describe how `m` is worse than the baseline, if it is: slower by more
+    /// than `tolerance` percent, or exploring or holding more states.
+    pub fn regression(&self, m: &Measure, tolerance: f64) -> Option<String> {
+        let entry = self.entries.get(&(m.level.clone(), m.strategy.name().to_string()))?;
+        let median = batch::millis(m.median());
+        let mut worse = vec![];
+        if median > entry.median * (1.0 + tolerance / 100.0) && median - entry.median > NOISE {
+            worse.push(format!("median {:.3} ms > {:.3} ms", median, entry.median));
+        }
+        if m.nodes > entry.nodes {
+            worse.push(format!("nodes {} > {}", m.nodes, entry.nodes));
+        }
+        if m.peak_states > entry.peak_states {
+            worse.push(format!("peak states {} > {}", m.peak_states, entry.peak_states));
+        }
+        if worse.is_empty() {
+            None
+        } else {
+            Some(worse.join(", "))
+        }
+    }
+
+    /// Relative change of the median time of `m`, in percent.
+    pub fn change(&self, m: &Measure) -> Option<f64> {
+        let entry = self.entries.get(&(m.level.clone(), m.strategy.name().to_string())).filter(|e| e.median > 0.0)?;
+        Some((batch::millis(m.median()) / entry.median - 1.0) * 100.0)
+    }
+}
+
+/// Print a table of `measures`, compared with `baseline` if any. Return the
+/// number of regressions.
+pub fn print(measures: &[Measure], baseline: Option<&Baseline>, tolerance: f64) -> usize {
+    println!("{:<12} {:<8} {:>11} {:>11} {:>9} {:>11} {:>8}", "level", "strategy", "median (ms)", "p90 (ms)", "nodes", "peak states", "change");
+    let mut regressions = vec![];
+    for m in measures {
+        println!("{:<12} {:<8} {:>11.3} {:>11.3} {:>9} {:>11} {:>8}{}",
+                 m.level, m.strategy.name(), batch::millis(m.median()), batch::millis(m.percentile(90)), m.nodes, m.peak_states,
+                 baseline.and_then(|b| b.change(m)).map_or("-".to_string(), |c| format!("{:+.1}%", c)),
+                 if m.solved { "" } else { "  (unsolved)" });
+        if let Some(regression) = baseline.and_then(|b| b.regression(m, tolerance)) {
+            regressions.push(format!("{} {}: {}", m.level, m.strategy.name(), regression));
+        }
+    }
+    if !regressions.is_empty() {
+        println!("\nREGRESSIONS:");
+        for r in regressions.iter() {
+            println!("  {}", r);
+        }
+    }
+    regressions.len()
+}
+
+pub struct Options {
+    pub runs: usize,
+    pub budget: usize,
+    pub strategies: Vec<solver::Strategy>,
+    /// Write the measures there.
+    pub save: Option<String>,
+    /// Compare the measures with the ones saved there.
+    pub baseline: Option<String>,
+    /// Percents a median time may grow before being a regression.
+    pub tolerance: f64,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            runs: 5,
+            budget: 100_000,
+            strategies: vec![solver::Strategy::DepthFirst, solver::Strategy::BreadthFirst],
+            save: None,
+            baseline: None,
+            tolerance: 20.0,
+        }
+    }
+}
+
+impl Options {
+    /// Read the options from arguments, returning the first one not
+    /// understood on error.
+    pub fn from_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut strategies = vec![];
+        while let Some(arg) = args.next() {
+            let value = match arg.as_str() {
+                "--runs" | "--budget" | "--strategy" | "--save" | "--baseline" | "--tolerance" => args.next().ok_or_else(|| arg.clone())?,
+                _ => return Err(arg),
+            };
+            let invalid = || format!("{} {}", arg, value);
+            match arg.as_str() {
+                "--runs" => options.runs = value.parse().map_err(|_| invalid())?,
+                "--budget" => options.budget = value.parse().map_err(|_| invalid())?,
+                "--strategy" => strategies.push(solver::Strategy::from_name(&value).ok_or_else(invalid)?),
+                "--save" => options.save = Some(value),
+                "--baseline" => options.baseline = Some(value),
+                _ => options.tolerance = value.parse().map_err(|_| invalid())?,
+            }
+        }
+        if !strategies.is_empty() {
+            options.strategies = strategies;
+        }
+        Ok(options)
+    }
+}
+
+/// Measure every built-in scenario with every strategy and print the
+/// results. Return the number of regressions against the baseline.
+pub fn run(options: &Options) -> Result<usize, String> {
+    let baseline = match options.baseline {
+        Some(ref file) => {
+            let s = fs::read_to_string(file).map_err(|e| format!("{}: {}", file, e))?;
+            Some(Baseline::from_csv(&s).map_err(|e| format!("{}: {}", file, e))?)
+        },
+        None => None,
+    };
+
+    let mut measures = vec![];
+    for (n, scenario) in scenario::all().into_iter().enumerate() {
+        let level = scenario.level();
+        for &strategy in options.strategies.iter() {
+            measures.push(measure(&format!("scenario-{}", n), &level, strategy, options.runs, options.budget));
+        }
+    }
+
+    let regressions = print(&measures, baseline.as_ref(), options.tolerance);
+    if let Some(ref file) = options.save {
+        fs::write(file, to_csv(&measures)).map_err(|e| format!("{}: {}", file, e))?;
+    }
+    Ok(regressions)
+}
@@ -0,0 +1,124 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path;
+use ::elem;
+use ::map;
+use ::solver;
+use ::canon;
+use ::solution;
+
+/// Where solutions are cached unless `RGB_EXPRESS_CACHE` says otherwise.
+pub const DEFAULT_DIR: &str = ".rgb-express-cache";
+
+/// A directory of verified solutions, one file per level, ruleset and
+/// search strategy. Levels equal up to symmetries share their entry, the
+/// solution being stored as played on the canonical form.
+pub struct Cache {
+    dir: path::PathBuf,
+}
+
+/// What a cache directory holds.
+pub struct Stats {
+    pub entries: usize,
+    pub bytes: u64,
+    /// Entries saved under another ruleset, never used.
+    pub stale: usize,
+}
+
+impl Cache {
+    pub fn new<P: Into<path::PathBuf>>(dir: P) -> Cache {
+        Cache { dir: dir.into() }
+    }
+
+    /// The cache in `RGB_EXPRESS_CACHE`, or in `DEFAULT_DIR`.
+    pub fn from_env() -> Cache {
+        Cache::new(env::var_os("RGB_EXPRESS_CACHE").unwrap_or_else(|| DEFAULT_DIR.into()))
+    }
+
+    pub fn dir(&self) -> &path::Path {
+        &self.dir
+    }
+
+    fn file(&self, map: &map::Map, cars: &[elem::Car], strategy: solver::Strategy) -> path::PathBuf {
+        self.dir.join(format!("{:016x}-r{}-{}.txt", canon::hash(map, cars), solver::RULESET, strategy.name()))
+    }
+
+    /// The solution of `map` and `cars` saved by a search with `strategy`,
+    /// if any. An entry which does not solve the level is removed.
+    pub fn get(&self, map: &map::Map, cars: &[elem::Car], strategy: solver::Strategy) -> Option<solver::Solution> {
+        let file = self.file(map, cars, strategy);
+        let s = fs::read_to_string(&file).ok()?;
+        let solution = s.parse::<solution::SolutionFile>().ok()
+                        .map(|saved| canon::frame(map, cars).from_canonical(map, &saved.solution));
+        match solution {
+            Some(solution) if solver::verify(map, cars, &solution) => Some(solution),
+            _ => {
+                let _ = fs::remove_file(&file);
+                None
+            },
+        }
+    }
+
+    pub fn put(&self, map: &map::Map, cars: &[elem::Car], strategy: solver::Strategy, solution: &solver::Solution) -> Result<(), String> {
+        let file = self.file(map, cars, strategy);
+        let solution = canon::frame(map, cars).to_canonical(map, solution);
+        fs::create_dir_all(&self.dir).map_err(|e| format!("{}: {}", self.dir.display(), e))?;
+        solution::SolutionFile::new(map, cars, solution).save(&file)
+    }
+
+    /// Solve with `s`, unless the solution is cached, and cache what it
+    /// finds.
+    pub fn solve(&self, s: &mut solver::Solver, map: &map::Map, cars: &[elem::Car]) -> Option<solver::Solution> {
+        if let Some(solution) = self.get(map, cars, s.strategy()) {
+            return Some(solution);
+        }
+        let solution = s.solve()?;
+        if let Err(e) = self.put(map, cars, s.strategy(), &solution) {
+            eprintln!("{}", e);
+        }
+        Some(solution)
+    }
+
+    pub fn stats(&self) -> Result<Stats, String> {
+        let mut stats = Stats { entries: 0, bytes: 0, stale: 0 };
+        let current = format!("-r{}-", solver::RULESET);
+        for (name, file) in self.entries()? {
+            stats.entries += 1;
+            stats.bytes += fs::metadata(&file).map_or(0, |m| m.len());
+            if !name.contains(&current) {
+                stats.stale += 1;
+            }
+        }
+        Ok(stats)
+    }
+
+    /// Remove every entry, returning how many there were.
+    pub fn clear(&self) -> Result<usize, String> {
+        let entries = self.entries()?;
+        for (_, file) in entries.iter() {
+            fs::remove_file(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+        }
+        let _ = fs::remove_dir(&self.dir);
+        Ok(entries.len())
+    }
+
+    /// The names and paths of the entries, leaving alone files not written
+    /// by the cache.
+    fn entries(&self) -> Result<Vec<(String, path::PathBuf)>, String> {
+        let dir = match fs::read_dir(&self.dir) {
+            Ok(dir) => dir,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(format!("{}: {}", self.dir.display(), e)),
+        };
+        let mut entries = vec![];
+        for entry in dir {
+            let entry = entry.map_err(|e| format!("{}: {}", self.dir.display(), e))?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.ends_with(".txt") && name.contains("-r") {
+                entries.push((name, entry.path()));
+            }
+        }
+        Ok(entries)
+    }
+}
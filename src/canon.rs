@@ -0,0 +1,175 @@
+use std::path;
+use ::elem;
+use ::map;
+use ::level;
+use ::transform;
+use ::batch;
+use ::solver;
+
+/// The representative of all the levels equal to `map` and `cars` up to
+/// empty borders, rotation, reflection and the order of the cars, in the
+/// level file syntax.
+pub fn canonical(map: &map::Map, cars: &[elem::Car]) -> String {
+    best(map, cars).0
+}
+
+/// How `map` and `cars` are turned into their canonical form.
+pub fn frame(map: &map::Map, cars: &[elem::Car]) -> Frame {
+    best(map, cars).1
+}
+
+/// How a level is turned into its canonical form, besides cutting its empty
+/// borders: symmetries applied in order, then a new order of the cars.
+pub struct Frame {
+    pub transforms: Vec<transform::Transform>,
+    /// The car `i` of the canonical form is the car `order[i]` of the level.
+    pub order: Vec<usize>,
+}
+
+impl Frame {
+    /// `solution` of `map` played on the canonical form of `map`.
+    pub fn to_canonical(&self, map: &map::Map, solution: &solver::Solution) -> solver::Solution {
+        let mut map = map.clone();
+        let mut solution = solution.clone();
+        for t in self.transforms.iter() {
+            solution = t.solution(&map, &solution);
+            map = t.map(&map);
+        }
+        solver::Solution {
+            dirs: solution.dirs.iter().map(|moves| self.order.iter().map(|&i| moves[i]).collect()).collect(),
+            targets: solution.targets,
+        }
+    }
+
+    /// `solution` of the canonical form of `map` played on `map`.
+    pub fn from_canonical(&self, map: &map::Map, solution: &solver::Solution) -> solver::Solution {
+        let mut maps = vec![map.clone()];
+        for t in self.transforms.iter() {
+            let next = t.map(maps.last().unwrap());
+            maps.push(next);
+        }
+        let mut solution = solver::Solution {
+            dirs: solution.dirs.iter().map(|moves| {
+                let mut dirs = vec![None; moves.len()];
+                for (&i, &dir) in self.order.iter().zip(moves.iter()) {
+                    dirs[i] = dir;
+                }
+                dirs
+            }).collect(),
+            targets: solution.targets.clone(),
+        };
+        // A quarter turn is undone by three more, a mirror by itself.
+        for (&t, map) in self.transforms.iter().zip(maps[1..].iter()).rev() {
+            let mut map = map.clone();
+            let turns = if t == transform::Transform::Rotate90 { 3 } else { 1 };
+            for _ in 0..turns {
+                solution = t.solution(&map, &solution);
+                map = t.map(&map);
+            }
+        }
+        solution
+    }
+}
+
+fn best(map: &map::Map, cars: &[elem::Car]) -> (String, Frame) {
+    let (map, cars) = trim(map, cars);
+    let mut best: Option<(String, Frame)> = None;
+    for mirror in [false, true] {
+        let mut transforms = vec![];
+        let (mut map, mut cars) = (map.clone(), cars.clone());
+        if mirror {
+            let t = transform::Transform::MirrorHorizontal;
+            cars = t.cars(&map, &cars).unwrap();
+            map = t.map(&map);
+            transforms.push(t);
+        }
+        for turns in 1..=4 {
+            let t = transform::Transform::Rotate90;
+            cars = t.cars(&map, &cars).unwrap();
+            map = t.map(&map);
+            let mut order: Vec<usize> = (0..cars.len()).collect();
+            order.sort_by_key(|&i| (cars[i].coord.1, cars[i].coord.0, cars[i].color.name()));
+            let sorted = order.iter().map(|&i| cars[i].clone()).collect();
+            let text = level::Level::new(map.clone(), sorted).to_string();
+            if best.as_ref().is_none_or(|best| text < best.0) {
+                let mut transforms = transforms.clone();
+                transforms.extend((0..turns % 4).map(|_| t));
+                best = Some((text, Frame { transforms, order }));
+            }
+        }
+    }
+    best.unwrap()
+}
+
+/// Stable hash of the canonical form of a level.
+pub fn hash(map: &map::Map, cars: &[elem::Car]) -> u64 {
+    fnv1a(canonical(map, cars).as_bytes())
+}
+
+/// Stable hash of a level up to empty borders only. Unlike `hash`, it tells
+/// apart rotated, mirrored and reordered copies, which take other moves.
+pub fn layout_hash(map: &map::Map, cars: &[elem::Car]) -> u64 {
+    let (map, cars) = trim(map, cars);
+    fnv1a(level::Level::new(map, cars).to_string().as_bytes())
+}
+
+/// The hash of `level` as written in file names and headers.
+pub fn level_hash(level: &level::Level) -> String {
+    format!("{:016x}", hash(&level.map, &level.cars))
+}
+
+/// Drop the empty rows and columns around the cells and cars, and forget the
+/// state the solver leaves on cells.
+fn trim(map: &map::Map, cars: &[elem::Car]) -> (map::Map, Vec<elem::Car>) {
+    let used: Vec<(usize, usize)> = (0..map.height).flat_map(|y| (0..map.width).map(move |x| (x, y)))
+                                                   .filter(|&coord| map[coord].typ != elem::Type::Empty)
+                                                   .chain(cars.iter().map(|car| car.coord))
+                                                   .collect();
+    let (x0, y0) = (used.iter().map(|c| c.0).min().unwrap_or(0), used.iter().map(|c| c.1).min().unwrap_or(0));
+    let (x1, y1) = (used.iter().map(|c| c.0 + 1).max().unwrap_or(1), used.iter().map(|c| c.1 + 1).max().unwrap_or(1));
+    let t = transform::Transform::Crop(transform::Rect { x: x0, y: y0, width: x1 - x0, height: y1 - y0 });
+    let mut trimmed = t.map(map);
+    for e in trimmed.iter_mut() {
+        if e.typ == elem::Type::DropOn {
+            e.typ = elem::Type::DropOff;
+        }
+    }
+    (trimmed, t.cars(map, cars).unwrap())
+}
+
+/// 64-bit FNV-1a.
+fn fnv1a(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |h, &b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Level files holding the same level.
+pub struct Group {
+    pub hash: u64,
+    pub files: Vec<path::PathBuf>,
+}
+
+/// Group the level files of `files` and of the directories among them by
+/// canonical hash, in order of first appearance. Files which cannot be read
+/// are returned apart.
+pub fn group(files: &[path::PathBuf]) -> Result<(Vec<Group>, Vec<String>), String> {
+    let mut groups: Vec<Group> = vec![];
+    let mut errors = vec![];
+    for file in files {
+        let files = if file.is_dir() { batch::level_files(file)? } else { vec![file.clone()] };
+        for file in files {
+            let level = match level::Level::load(&file) {
+                Ok(level) => level,
+                Err(e) => {
+                    errors.push(e);
+                    continue;
+                },
+            };
+            let hash = hash(&level.map, &level.cars);
+            match groups.iter_mut().find(|g| g.hash == hash) {
+                Some(g) => g.files.push(file),
+                None => groups.push(Group { hash, files: vec![file] }),
+            }
+        }
+    }
+    Ok((groups, errors))
+}
@@ -0,0 +1,89 @@
+use std::f64;
+use ::elem;
+use ::solver;
+use ::level;
+
+/// Search budget of every solver run.
+const BUDGET: usize = 1_000_000;
+
+/// How hard a level is, as seen by the solver.
+#[derive(Clone, Debug)]
+pub struct DifficultyReport {
+    /// States explored before the first solution.
+    pub nodes: usize,
+    /// Average number of legal moves from a state.
+    pub branching: f64,
+    pub solutions: usize,
+    /// False if the budget ran out before every solution was found, in
+    /// which case the other measures only cover the solutions found.
+    pub complete: bool,
+    /// Ticks of the shortest solution.
+    pub shortest: Option<usize>,
+    /// Fewest buttons pressed, and cubes dropped, by a solution.
+    pub toggles: usize,
+    pub drops: usize,
+    /// The higher the harder. Levels without solution score infinity.
+    pub score: f64,
+}
+
+pub fn difficulty(level: &level::Level) -> DifficultyReport {
+    let mut s = solver::Solver::new(level.map.clone(), level.cars.clone());
+    s.set_budget(BUDGET);
+    let first = s.solve();
+    let nodes = s.nodes();
+
+    let mut s = solver::Solver::new(level.map.clone(), level.cars.clone());
+    s.set_budget(BUDGET);
+    let mut solutions = 0;
+    let mut shortest: Option<usize> = None;
+    let mut toggles = usize::MAX;
+    let mut drops = usize::MAX;
+    s.each_solution(&mut |solution| {
+        let (t, d) = uses(level, &solution);
+        solutions += 1;
+        shortest = Some(shortest.map_or(solution.dirs.len(), |n| n.min(solution.dirs.len())));
+        toggles = toggles.min(t);
+        drops = drops.min(d);
+    });
+    let branching = s.branching();
+    let complete = !s.exhausted();
+
+    let score = match (first, shortest) {
+        (Some(_), Some(shortest)) => {
+            let score = (nodes as f64).log2() + branching + shortest as f64 / 2.0
+                        + 2.0 * (toggles + drops) as f64 - (solutions as f64).log2();
+            score.max(0.0)
+        },
+        _ => f64::INFINITY,
+    };
+    DifficultyReport {
+        nodes,
+        branching,
+        solutions,
+        complete,
+        shortest,
+        toggles: if solutions > 0 { toggles } else { 0 },
+        drops: if solutions > 0 { drops } else { 0 },
+        score,
+    }
+}
+
+/// Count the buttons pressed and the cubes dropped by `solution`.
+fn uses(level: &level::Level, solution: &solver::Solution) -> (usize, usize) {
+    let frames = solver::simulate(&level.map, &level.cars, solution);
+    let mut toggles = 0;
+    let mut drops = 0;
+    for (before, after) in frames.iter().zip(frames.iter().skip(1)) {
+        for (car, start) in after.1.iter().zip(before.1.iter()) {
+            if car.coord == start.coord {
+                continue;
+            }
+            match (before.0[car.coord].typ, after.0[car.coord].typ) {
+                (elem::Type::ArmedButton(_), _) => toggles += 1,
+                (elem::Type::DropOn, elem::Type::Cube(_)) => drops += 1,
+                _ => {},
+            }
+        }
+    }
+    (toggles, drops)
+}
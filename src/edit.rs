@@ -0,0 +1,212 @@
+use std::io::{self, Read, Write};
+use std::path;
+use ::map;
+use ::elem;
+use ::solver;
+use ::render;
+use ::level;
+use ::term;
+use ::route;
+use ::cache;
+
+const HELP: &str = "\
+arrows: move cursor, w/a/s/d: toggle road up/left/down/right, space: clear
+x: road, O: drop zone, r/g/b/y: cube, R/G/B/Y: house,
+^/v/~/#: armed button/pushed button/open bridge/closed bridge (again to change colour)
+t: add truck or change its colour, T: remove truck, u: undo
+enter: solve now, S: save, q: quit";
+
+/// States the solver may explore when asked to solve, to keep the editor
+/// responsive.
+const SOLVE_BUDGET: usize = 100_000;
+
+/// Colours of buttons and bridges, in the order they are cycled through.
+const SWITCHES: [elem::Color; 4] = [elem::Color::Pink, elem::Color::Violet, elem::Color::Orange, elem::Color::Cream];
+
+/// Colours of trucks, in the order they are cycled through.
+const TRUCKS: [elem::Color; 5] = [elem::Color::Red, elem::Color::Green, elem::Color::Blue, elem::Color::Yellow, elem::Color::White];
+
+struct Editor {
+    level: level::Level,
+    cursor: (usize, usize),
+    history: Vec<(map::Map, Vec<elem::Car>)>,
+    modified: bool,
+}
+
+fn next<T: Copy + PartialEq>(all: &[T], current: T) -> T {
+    let i = all.iter().position(|&c| c == current).map_or(0, |i| i + 1);
+    all[i % all.len()]
+}
+
+impl Editor {
+    fn snapshot(&mut self) {
+        self.history.push((self.level.map.clone(), self.level.cars.clone()));
+        self.modified = true;
+    }
+
+    fn undo(&mut self) {
+        if let Some((map, cars)) = self.history.pop() {
+            self.level.map = map;
+            self.level.cars = cars;
+        }
+    }
+
+    fn go(&mut self, dir: elem::Dir) {
+        if let Some(coord) = self.neighbour(dir) {
+            self.cursor = coord;
+        }
+    }
+
+    fn neighbour(&self, dir: elem::Dir) -> Option<(usize, usize)> {
+        self.level.map.neighbour(self.cursor, dir)
+    }
+
+    /// Connect the cursor to its neighbour, or disconnect them.
+    fn toggle_road(&mut self, dir: elem::Dir) {
+        if let Some(other) = self.neighbour(dir) {
+            self.snapshot();
+            let map = &mut self.level.map;
+            if map[self.cursor].connected(dir) {
+                map[self.cursor].disconnect(dir);
+                map[other].disconnect(dir.rev());
+            } else {
+                map[self.cursor].connect(dir);
+                map[other].connect(dir.rev());
+            }
+        }
+    }
+
+    fn place(&mut self, typ: elem::Type) {
+        self.snapshot();
+        self.level.map[self.cursor].typ = typ;
+    }
+
+    /// Place a button or bridge, changing its colour if the cell already
+    /// holds one of the same kind.
+    fn place_switch(&mut self, make: fn(elem::Color) -> elem::Type) {
+        let color = SWITCHES.iter().cloned()
+                            .find(|&c| self.level.map[self.cursor].typ == make(c))
+                            .map_or(SWITCHES[0], |c| next(&SWITCHES, c));
+        self.place(make(color));
+    }
+
+    fn clear(&mut self) {
+        self.snapshot();
+        for &dir in [elem::Dir::Up, elem::Dir::Right, elem::Dir::Down, elem::Dir::Left].iter() {
+            if let Some(other) = self.neighbour(dir) {
+                self.level.map[other].disconnect(dir.rev());
+            }
+        }
+        self.level.map[self.cursor] = elem::Elem::default();
+        let cursor = self.cursor;
+        self.level.cars.retain(|car| car.coord != cursor);
+    }
+
+    fn truck(&mut self) {
+        self.snapshot();
+        let cursor = self.cursor;
+        match self.level.cars.iter_mut().find(|car| car.coord == cursor) {
+            Some(car) => car.color = next(&TRUCKS, car.color),
+            None => self.level.cars.push(elem::Car::new(cursor.0, cursor.1, TRUCKS[0])),
+        }
+    }
+
+    fn remove_truck(&mut self) {
+        self.snapshot();
+        let cursor = self.cursor;
+        self.level.cars.retain(|car| car.coord != cursor);
+    }
+
+    fn solve(&self, r: &dyn render::Renderer) -> String {
+        let mut s = solver::Solver::new(self.level.map.clone(), self.level.cars.clone());
+        s.set_budget(SOLVE_BUDGET);
+        match cache::Cache::from_env().solve(&mut s, &self.level.map, &self.level.cars) {
+            Some(solution) => {
+                let mut map = self.level.map.clone();
+                map.set_targets(&solution.targets);
+                let mut out = format!("SOLUTION FOUND in {} ticks:\n", solution.dirs.len());
+                out.push_str(&map.render_solution(&solution.dirs, &self.level.cars, &[], r));
+                out.push('\n');
+                for (i, car) in self.level.cars.iter().enumerate() {
+                    out.push_str(&format!("{}: {}\n", r.car(i, car), r.route(&route::Route::of(&solution, i).trimmed())));
+                }
+                out
+            },
+            None if s.exhausted() => format!("GAVE UP after {} states, the level may still be solvable", s.nodes()),
+            None => "NO SOLUTION FOUND".to_string(),
+        }
+    }
+
+    fn draw(&self, path: &path::Path, r: &dyn render::Renderer, message: &str) {
+        let mut out = String::from(term::clear());
+        let (x, y) = self.cursor;
+        out.push_str(&format!("{}{} - cursor ({}, {}): {:?}\n\n",
+                              path.display(), if self.modified { " [modified]" } else { "" },
+                              x, y, self.level.map[self.cursor].typ));
+        // Point at the cursor row and column.
+        for (n, line) in self.level.map.render_cars(r, &self.level.cars).lines().enumerate() {
+            out.push_str(line);
+            if n == 2 * y {
+                out.push_str(" <");
+            }
+            out.push('\n');
+        }
+        out.push_str(&format!("{}^\n\n{}\n\n{}\n", " ".repeat(3 * x), HELP, message));
+        print!("{}", out);
+        io::stdout().flush().unwrap();
+    }
+}
+
+/// Edit `level` in the terminal, saving it to `path` on demand.
+pub fn edit(level: level::Level, path: &path::Path, r: &dyn render::Renderer) {
+    let mut editor = Editor {
+        level,
+        cursor: (0, 0),
+        history: vec![],
+        modified: false,
+    };
+
+    let _raw = term::RawMode::new();
+    let stdin = io::stdin();
+    let mut input = stdin.lock().bytes().map_while(Result::ok);
+    let mut message = String::new();
+    loop {
+        editor.draw(path, r, &message);
+        message.clear();
+        let key = match term::read_key(&mut input) {
+            Some(key) => key,
+            None => return,
+        };
+        match key {
+            term::Key::Up => editor.go(elem::Dir::Up),
+            term::Key::Right => editor.go(elem::Dir::Right),
+            term::Key::Down => editor.go(elem::Dir::Down),
+            term::Key::Left => editor.go(elem::Dir::Left),
+            term::Key::Char('w') => editor.toggle_road(elem::Dir::Up),
+            term::Key::Char('d') => editor.toggle_road(elem::Dir::Right),
+            term::Key::Char('s') => editor.toggle_road(elem::Dir::Down),
+            term::Key::Char('a') => editor.toggle_road(elem::Dir::Left),
+            term::Key::Char(' ') | term::Key::Backspace => editor.clear(),
+            term::Key::Char('x') => editor.place(elem::Type::Road),
+            term::Key::Char('O') => editor.place(elem::Type::DropOff),
+            term::Key::Char(c @ ('r' | 'g' | 'b' | 'y' | 'R' | 'G' | 'B' | 'Y')) => editor.place(elem::Elem::from_char(c, ' ').typ),
+            term::Key::Char('^') => editor.place_switch(elem::Type::ArmedButton),
+            term::Key::Char('v') => editor.place_switch(elem::Type::PushedButton),
+            term::Key::Char('~') => editor.place_switch(elem::Type::OpenBridge),
+            term::Key::Char('#') => editor.place_switch(elem::Type::ClosedBridge),
+            term::Key::Char('t') => editor.truck(),
+            term::Key::Char('T') => editor.remove_truck(),
+            term::Key::Char('u') => editor.undo(),
+            term::Key::Enter => message = editor.solve(r),
+            term::Key::Char('S') => message = match editor.level.save(path) {
+                Ok(()) => {
+                    editor.modified = false;
+                    format!("saved to {}", path.display())
+                },
+                Err(e) => e,
+            },
+            term::Key::Char('q') => return,
+            _ => {},
+        }
+    }
+}
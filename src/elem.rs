@@ -29,6 +29,43 @@ impl Color {
         }, s)
     }
 
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Color::Red    => "red",
+            Color::Green  => "green",
+            Color::Blue   => "blue",
+            Color::Yellow => "yellow",
+            Color::White  => "white",
+            Color::Orange => "orange",
+            Color::Violet => "violet",
+            Color::Pink   => "pink",
+            Color::Cream  => "cream",
+        }
+    }
+
+    /// Inverse of `name`.
+    pub fn from_name(name: &str) -> Option<Color> {
+        [Color::Red, Color::Green, Color::Blue, Color::Yellow, Color::White,
+         Color::Orange, Color::Violet, Color::Pink, Color::Cream].iter()
+                                                                 .find(|c| c.name() == name)
+                                                                 .cloned()
+    }
+
+    /// Inverse of `from_char`.
+    pub fn to_char(self) -> char {
+        match self {
+            Color::Red    => 'r',
+            Color::Green  => 'g',
+            Color::Blue   => 'b',
+            Color::Yellow => 'y',
+            Color::White  => 'w',
+            Color::Orange => 'o',
+            Color::Violet => 'v',
+            Color::Pink   => 'p',
+            Color::Cream  => 'c',
+        }
+    }
+
     pub fn from_char(c: char) -> Option<Color> {
         match c {
             'r' => Some(Color::Red),
@@ -45,7 +82,7 @@ impl Color {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Type {
     Empty,
     Road,
@@ -70,7 +107,7 @@ pub struct Car {
 impl Car {
     pub fn new(x: usize, y: usize, color: Color) -> Car {
         Car {
-            color: color,
+            color,
             coord: (x, y),
             cubes: vec![],
         }
@@ -96,7 +133,6 @@ impl fmt::Display for Car {
 pub struct Elem {
     pub conn: [bool; 4],
     pub typ: Type,
-    pub occupied: bool,
 }
 
 impl Elem {
@@ -120,7 +156,6 @@ impl Elem {
                 '#' => Type::ClosedBridge(Color::from_char(x).unwrap()),
                 _   => Type::Empty,
             },
-            occupied: false,
         }
     }
     pub fn connect(&mut self, dir: Dir) {
@@ -169,6 +204,15 @@ impl Dir {
             Dir::Left  => Dir::Right,
         }
     }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Dir::Up    => "up",
+            Dir::Right => "right",
+            Dir::Down  => "down",
+            Dir::Left  => "left",
+        }
+    }
 }
 
 impl fmt::Display for Dir {
@@ -187,7 +231,6 @@ impl Default for Elem {
         Elem {
             conn: [false; 4],
             typ: Type::Empty,
-            occupied: false,
         }
     }
 }
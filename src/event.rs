@@ -0,0 +1,93 @@
+use std::fmt;
+use ::elem;
+
+/// Something happening during a tick, in the order the rules apply it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Event {
+    Moved { car: usize, from: (usize, usize), to: (usize, usize), dir: elem::Dir },
+    PickedUp { car: usize, cube: elem::Color, coord: (usize, usize) },
+    Delivered { car: usize, house: elem::Color, coord: (usize, usize) },
+    /// The top cube of the car was left on a drop zone.
+    Dropped { car: usize, cube: elem::Color, coord: (usize, usize) },
+    ButtonPressed { car: usize, color: elem::Color, coord: (usize, usize) },
+    BridgeOpened { color: elem::Color, coord: (usize, usize) },
+    BridgeClosed { color: elem::Color, coord: (usize, usize) },
+    /// The car waits, and will until the end.
+    Stopped { car: usize },
+    /// The car ran into another one standing on `coord`.
+    Collision { car: usize, coord: (usize, usize) },
+}
+
+/// Where the rules report events. `()` ignores them, for the solver.
+pub trait Sink {
+    fn push(&mut self, event: Event);
+}
+
+impl Sink for Vec<Event> {
+    fn push(&mut self, event: Event) {
+        Vec::push(self, event);
+    }
+}
+
+impl Sink for () {
+    fn push(&mut self, _: Event) {}
+}
+
+impl Event {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Event::Moved { .. }         => "moved",
+            Event::PickedUp { .. }      => "picked_up",
+            Event::Delivered { .. }     => "delivered",
+            Event::Dropped { .. }       => "dropped",
+            Event::ButtonPressed { .. } => "button_pressed",
+            Event::BridgeOpened { .. }  => "bridge_opened",
+            Event::BridgeClosed { .. }  => "bridge_closed",
+            Event::Stopped { .. }       => "stopped",
+            Event::Collision { .. }     => "collision",
+        }
+    }
+
+    /// The car the event happened to, if any.
+    pub fn car(&self) -> Option<usize> {
+        match *self {
+            Event::Moved { car, .. } | Event::PickedUp { car, .. } | Event::Delivered { car, .. } |
+            Event::Dropped { car, .. } | Event::ButtonPressed { car, .. } | Event::Stopped { car } |
+            Event::Collision { car, .. } => Some(car),
+            Event::BridgeOpened { .. } | Event::BridgeClosed { .. } => None,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let coord = |(x, y): (usize, usize)| format!("[{},{}]", x, y);
+        let fields = match *self {
+            Event::Moved { car, from, to, dir } =>
+                format!("\"car\":{},\"from\":{},\"to\":{},\"dir\":\"{}\"", car, coord(from), coord(to), dir.name()),
+            Event::PickedUp { car, cube: color, coord: c } | Event::Delivered { car, house: color, coord: c } |
+            Event::Dropped { car, cube: color, coord: c } | Event::ButtonPressed { car, color, coord: c } =>
+                format!("\"car\":{},\"color\":\"{}\",\"coord\":{}", car, color.name(), coord(c)),
+            Event::BridgeOpened { color, coord: c } | Event::BridgeClosed { color, coord: c } =>
+                format!("\"color\":\"{}\",\"coord\":{}", color.name(), coord(c)),
+            Event::Stopped { car } => format!("\"car\":{}", car),
+            Event::Collision { car, coord: c } => format!("\"car\":{},\"coord\":{}", car, coord(c)),
+        };
+        format!("{{\"event\":\"{}\",{}}}", self.name(), fields)
+    }
+}
+
+/// A play-by-play sentence.
+impl fmt::Display for Event {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            Event::Moved { car, to, dir, .. } => write!(fmt, "truck {} moves {} to ({}, {})", car, dir.name(), to.0, to.1),
+            Event::PickedUp { car, cube, coord } => write!(fmt, "truck {} picks up a {} cube at ({}, {})", car, cube.name(), coord.0, coord.1),
+            Event::Delivered { car, house, coord } => write!(fmt, "truck {} delivers the {} house at ({}, {})", car, house.name(), coord.0, coord.1),
+            Event::Dropped { car, cube, coord } => write!(fmt, "truck {} drops a {} cube at ({}, {})", car, cube.name(), coord.0, coord.1),
+            Event::ButtonPressed { car, color, coord } => write!(fmt, "truck {} presses the {} button at ({}, {})", car, color.name(), coord.0, coord.1),
+            Event::BridgeOpened { color, coord } => write!(fmt, "the {} bridge at ({}, {}) opens", color.name(), coord.0, coord.1),
+            Event::BridgeClosed { color, coord } => write!(fmt, "the {} bridge at ({}, {}) closes", color.name(), coord.0, coord.1),
+            Event::Stopped { car } => write!(fmt, "truck {} stops", car),
+            Event::Collision { car, coord } => write!(fmt, "truck {} runs into another truck at ({}, {})", car, coord.0, coord.1),
+        }
+    }
+}
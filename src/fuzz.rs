@@ -0,0 +1,63 @@
+//! Bodies of the fuzz targets of `fuzz/`, also run over their corpus by the
+//! tests. Fuzz with `cargo fuzz run parser` or `cargo fuzz run simulator`;
+//! the seed corpus holds the built-in scenarios and their solutions.
+
+use std::str;
+use ::elem;
+use ::map;
+use ::level;
+use ::solver;
+use ::scenario;
+
+/// Read arbitrary text as a map and as a level. Either is an error or a map
+/// written back in a text read again.
+pub fn parse(data: &[u8]) {
+    let s = match str::from_utf8(data) {
+        Ok(s) => s,
+        Err(_) => return,
+    };
+    if let Ok(map) = s.parse::<map::Map>() {
+        map.to_text().parse::<map::Map>().expect("written map is read back");
+    }
+    if let Ok(level) = s.parse::<level::Level>() {
+        level.to_string().parse::<level::Level>().expect("written level is read back");
+    }
+}
+
+/// Play arbitrary moves on a built-in scenario. The first byte picks the
+/// scenario, and turns on every drop zone if its high bit is set; then every
+/// byte is the move of a car, tick after tick.
+pub fn simulate(data: &[u8]) {
+    let (&first, moves) = match data.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+    let scenarios = scenario::all();
+    let level = scenarios[(first & 0x7f) as usize % scenarios.len()].level();
+    let zones = level.map.iter().filter(|e| matches!(e.typ, elem::Type::DropOff)).count();
+    let solution = solver::Solution {
+        dirs: moves.chunks_exact(level.cars.len()).map(|tick| tick.iter().map(|&b| decode(b)).collect()).collect(),
+        targets: vec![first & 0x80 != 0; zones],
+    };
+    solver::simulate(&level.map, &level.cars, &solution);
+}
+
+/// The input of `simulate` playing `solution` on the `n`-th scenario, with
+/// every drop zone on if `solution` uses any.
+pub fn encode(n: usize, solution: &solver::Solution) -> Vec<u8> {
+    let mut data = vec![n as u8 | if solution.targets.iter().any(|&on| on) { 0x80 } else { 0 }];
+    for moves in solution.dirs.iter() {
+        data.extend(moves.iter().map(|dir| dir.map_or(0, |d| d as u8 + 1)));
+    }
+    data
+}
+
+fn decode(b: u8) -> Option<elem::Dir> {
+    match b % 5 {
+        1 => Some(elem::Dir::Up),
+        2 => Some(elem::Dir::Right),
+        3 => Some(elem::Dir::Down),
+        4 => Some(elem::Dir::Left),
+        _ => None,
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+use std::mem;
+use ::map;
+use ::elem;
+use ::event;
+
+/// The position of a game between two ticks.
+#[derive(Clone)]
+pub struct Snapshot {
+    pub map: map::Map,
+    pub cars: Vec<elem::Car>,
+    /// The moves of the last tick, empty before the first one.
+    pub from: Vec<Option<elem::Dir>>,
+}
+
+impl Snapshot {
+    /// The position once `moves` are played, reporting to `events` what
+    /// happens. On error, the index of the faulty car is returned along with
+    /// the reason.
+    ///
+    /// Cars move all at once, so the outcome does not depend on their order:
+    /// a car may follow another one into the cell it leaves, but two cars
+    /// entering the same cell, swapping cells or running into a waiting car
+    /// collide. Buttons are pressed once every car moved, each color at most
+    /// once however many cars ran over its buttons, so a bridge is crossed
+    /// as it was at the start of the tick.
+    pub fn next<S: event::Sink>(&self, moves: &[Option<elem::Dir>], events: &mut S) -> Result<Snapshot, (usize, map::MoveError)> {
+        // A car which waited is stopped for good.
+        if let Some(i) = self.from.iter().zip(moves.iter()).position(|(last_dir, dir)| last_dir.is_none() && dir.is_some()) {
+            return Err((i, map::MoveError::Stopped));
+        }
+        for (i, dir) in moves.iter().enumerate() {
+            if dir.is_none() && self.from.get(i).is_none_or(|last_dir| last_dir.is_some()) {
+                events.push(event::Event::Stopped { car: i });
+            }
+        }
+
+        // Where every car ends the tick.
+        let mut to = Vec::with_capacity(self.cars.len());
+        for (i, car) in self.cars.iter().enumerate() {
+            to.push(match moves.get(i).cloned().flatten() {
+                Some(dir) if !self.map[car.coord].connected(dir) => return Err((i, map::MoveError::NoRoad)),
+                Some(dir) => self.map.neighbour(car.coord, dir).ok_or((i, map::MoveError::OffMap))?,
+                None => car.coord,
+            });
+        }
+        if let Some((i, coord)) = collision(&self.cars, &to) {
+            events.push(event::Event::Collision { car: i, coord });
+            return Err((i, map::MoveError::Collision));
+        }
+
+        let mut next = self.clone();
+        let mut pressed = vec![];
+        for (i, (dir, car)) in moves.iter().zip(next.cars.iter_mut()).enumerate() {
+            match next.map.drive(i, car, *dir, events).map_err(|e| (i, e))? {
+                Some(color) if !pressed.contains(&color) => pressed.push(color),
+                _ => {},
+            }
+        }
+        for color in pressed {
+            next.map.press(color, events);
+        }
+        next.map.check(&next.cars)?;
+        next.from = moves.to_vec();
+        Ok(next)
+    }
+}
+
+/// The first moving car, with the cell where it hits another one, if two
+/// cars end up on the same cell or cross each other on the same road.
+fn collision(cars: &[elem::Car], to: &[(usize, usize)]) -> Option<(usize, (usize, usize))> {
+    (0..cars.len()).filter(|&i| to[i] != cars[i].coord).find_map(|i| {
+        (0..cars.len()).find(|&j| j != i && (to[j] == to[i] || (to[j] == cars[i].coord && cars[j].coord == to[i])))
+                       .map(|_| (i, to[i]))
+    })
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        self.map.fmt(fmt)
+    }
+}
+
+/// A level being played tick by tick under the rules of the game, keeping
+/// the earlier positions to undo the ticks.
+#[derive(Clone)]
+pub struct Game {
+    current: Snapshot,
+    history: Vec<Snapshot>,
+}
+
+impl Game {
+    pub fn new(map: map::Map, cars: Vec<elem::Car>) -> Game {
+        Game::from_snapshot(Snapshot { map, cars, from: vec![] })
+    }
+
+    pub fn from_snapshot(snapshot: Snapshot) -> Game {
+        Game {
+            current: snapshot,
+            history: vec![],
+        }
+    }
+
+    /// Choose which drop zones, in reading order, take cubes. Meant to be
+    /// called before the first tick.
+    pub fn set_targets(&mut self, targets: &[bool]) {
+        self.current.map.set_targets(targets);
+    }
+
+    pub fn map(&self) -> &map::Map {
+        &self.current.map
+    }
+
+    pub fn cars(&self) -> &[elem::Car] {
+        &self.current.cars
+    }
+
+    /// Number of ticks played.
+    pub fn tick(&self) -> usize {
+        self.history.len()
+    }
+
+    /// Play a tick, `moves[i]` being the move of the car `i`, and return
+    /// what happened. Nothing changes on error.
+    pub fn step(&mut self, moves: &[Option<elem::Dir>]) -> Result<Vec<event::Event>, map::MoveError> {
+        let mut events = vec![];
+        self.step_events(moves, &mut events).map_err(|(_, e)| e)?;
+        Ok(events)
+    }
+
+    /// `step`, reporting to `events` what happens and on error which car
+    /// broke the rules.
+    pub fn step_events<S: event::Sink>(&mut self, moves: &[Option<elem::Dir>], events: &mut S) -> Result<(), (usize, map::MoveError)> {
+        let next = self.current.next(moves, events)?;
+        self.history.push(mem::replace(&mut self.current, next));
+        Ok(())
+    }
+
+    /// Whether every house got its cube.
+    pub fn is_won(&self) -> bool {
+        self.current.map.is_solved()
+    }
+
+    /// Take back the last tick, if any.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some(snapshot) => {
+                self.current = snapshot;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Take back every tick.
+    pub fn rewind(&mut self) {
+        if !self.history.is_empty() {
+            self.current = self.history.swap_remove(0);
+            self.history.clear();
+        }
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        self.current.clone()
+    }
+
+    /// Go back to `snapshot`, forgetting the ticks played.
+    pub fn restore(&mut self, snapshot: Snapshot) {
+        self.current = snapshot;
+        self.history.clear();
+    }
+}
@@ -0,0 +1,182 @@
+use ::vec2d;
+use ::elem;
+use ::map;
+use ::solver;
+use ::level;
+use ::rng;
+
+/// Colours of the trucks, in order.
+const TRUCKS: [elem::Color; 5] = [elem::Color::Red, elem::Color::Green, elem::Color::Blue, elem::Color::Yellow, elem::Color::White];
+/// Colours of the cubes and houses, in order.
+const CARGO: [elem::Color; 4] = [elem::Color::Red, elem::Color::Green, elem::Color::Blue, elem::Color::Yellow];
+/// Search budget of the solver for a candidate level.
+const BUDGET: usize = 200_000;
+/// Candidate levels tried before giving up.
+pub const ATTEMPTS: usize = 100;
+
+/// What `generate` should build.
+#[derive(Clone, Debug)]
+pub struct Params {
+    pub width: usize,
+    pub height: usize,
+    pub trucks: usize,
+    /// Number of cube colours, at most 4.
+    pub colors: usize,
+    /// Number of cubes, and houses, of every colour.
+    pub cubes: usize,
+    /// Add a button and the bridge it operates.
+    pub buttons: bool,
+    pub drop_zones: bool,
+    pub seed: u64,
+}
+
+impl Default for Params {
+    fn default() -> Params {
+        Params {
+            width: 5,
+            height: 5,
+            trucks: 2,
+            colors: 2,
+            cubes: 1,
+            buttons: false,
+            drop_zones: false,
+            seed: 0,
+        }
+    }
+}
+
+/// Build a random level the solver can solve. Fail if the parameters are
+/// out of range or none of the first `ATTEMPTS` candidates is solvable. The
+/// same parameters always give the same level.
+pub fn generate(params: &Params) -> Result<level::Level, String> {
+    if params.trucks == 0 || params.trucks > TRUCKS.len() {
+        return Err(format!("between 1 and {} trucks", TRUCKS.len()));
+    }
+    if params.colors == 0 || params.colors > CARGO.len() {
+        return Err(format!("between 1 and {} colors", CARGO.len()));
+    }
+    let cells = params.trucks + 2 * params.colors * params.cubes
+                + if params.buttons { 2 } else { 0 }
+                + if params.drop_zones { 1 } else { 0 };
+    if params.width * params.height < 2 * cells {
+        return Err(format!("a {}x{} map is too small, it takes {} cells at least", params.width, params.height, 2 * cells));
+    }
+
+    let mut rng = rng::Rng::new(params.seed);
+    for _ in 0..ATTEMPTS {
+        if let Some((map, cars)) = candidate(params, &mut rng) {
+            let mut s = solver::Solver::new(map.clone(), cars.clone());
+            s.set_budget(BUDGET);
+            if s.solve().is_some() {
+                let mut level = level::Level::new(map, cars);
+                level.header.push(("seed".to_string(), params.seed.to_string()));
+                return Ok(level);
+            }
+        }
+    }
+    Err(format!("seed {}: no solvable level in {} attempts", params.seed, ATTEMPTS))
+}
+
+/// Drive every truck along a random walk, then lay the cargo on its way.
+/// Return None if there is no room left for the cargo.
+fn candidate(params: &Params, rng: &mut rng::Rng) -> Option<(map::Map, Vec<elem::Car>)> {
+    let mut map: map::Map = vec2d::Vec2D::new(params.width, params.height);
+    let mut starts: Vec<(usize, usize)> = (0..params.height).flat_map(|y| (0..params.width).map(move |x| (x, y))).collect();
+    rng.shuffle(&mut starts);
+    let starts = &starts[..params.trucks];
+
+    let length = params.width * params.height / params.trucks;
+    let mut walks = vec![];
+    for &start in starts {
+        let length = length / 2 + rng.below(length / 2 + 1);
+        walks.push(walk(&mut map, start, length, rng));
+    }
+    // A few roads leading nowhere useful.
+    for _ in 0..(params.width * params.height) / 8 {
+        let coord = (rng.below(params.width), rng.below(params.height));
+        let dir = *rng.pick(&[elem::Dir::Up, elem::Dir::Right, elem::Dir::Down, elem::Dir::Left]);
+        if let Some(next) = map.neighbour(coord, dir) {
+            connect(&mut map, coord, next, dir);
+        }
+    }
+
+    // Cargo only goes on cells crossed once, so that it is met in order.
+    let mut visits = vec2d::Vec2D::<usize>::new(params.width, params.height);
+    for walk in walks.iter() {
+        for &coord in walk.iter() {
+            visits[coord] += 1;
+        }
+    }
+    let mut free: Vec<Vec<usize>> = walks.iter().map(|walk| {
+        (1..walk.len()).filter(|&i| visits[walk[i]] == 1 && !starts.contains(&walk[i])).collect()
+    }).collect();
+
+    for &color in CARGO[..params.colors].iter() {
+        for _ in 0..params.cubes {
+            let (cube, house) = pair(&walks, &mut free, rng)?;
+            map[cube].typ = elem::Type::Cube(color);
+            map[house].typ = elem::Type::House(color);
+        }
+    }
+    if params.buttons {
+        let (button, bridge) = pair(&walks, &mut free, rng)?;
+        map[button].typ = elem::Type::ArmedButton(elem::Color::Pink);
+        map[bridge].typ = elem::Type::OpenBridge(elem::Color::Pink);
+    }
+    if params.drop_zones {
+        let w = rng.below(walks.len());
+        if free[w].is_empty() {
+            return None;
+        }
+        let i = rng.below(free[w].len());
+        map[walks[w][free[w].remove(i)]].typ = elem::Type::DropOff;
+    }
+
+    let cars = starts.iter().zip(TRUCKS.iter()).map(|(&(x, y), &color)| elem::Car::new(x, y, color)).collect();
+    Some((map, cars))
+}
+
+/// Take two free cells of the same walk, in the order they are driven on.
+fn pair(walks: &[Vec<(usize, usize)>], free: &mut [Vec<usize>], rng: &mut rng::Rng) -> Option<((usize, usize), (usize, usize))> {
+    let w = rng.below(walks.len());
+    if free[w].len() < 2 {
+        return None;
+    }
+    let a = free[w].remove(rng.below(free[w].len()));
+    let b = free[w].remove(rng.below(free[w].len()));
+    Some((walks[w][a.min(b)], walks[w][a.max(b)]))
+}
+
+/// Random walk of at most `length` moves from `start`, never using a road
+/// twice. Return the cells driven on, `start` included.
+fn walk(map: &mut map::Map, start: (usize, usize), length: usize, rng: &mut rng::Rng) -> Vec<(usize, usize)> {
+    let mut coord = start;
+    let mut cells = vec![start];
+    if map[start].typ == elem::Type::Empty {
+        map[start].typ = elem::Type::Road;
+    }
+    for _ in 0..length {
+        let moves: Vec<_> = [elem::Dir::Up, elem::Dir::Right, elem::Dir::Down, elem::Dir::Left].iter()
+            .filter(|&&dir| !map[coord].connected(dir))
+            .filter_map(|&dir| map.neighbour(coord, dir).map(|next| (dir, next)))
+            .collect();
+        if moves.is_empty() {
+            break;
+        }
+        let (dir, next) = *rng.pick(&moves);
+        connect(map, coord, next, dir);
+        coord = next;
+        cells.push(coord);
+    }
+    cells
+}
+
+fn connect(map: &mut map::Map, from: (usize, usize), to: (usize, usize), dir: elem::Dir) {
+    for &coord in [from, to].iter() {
+        if map[coord].typ == elem::Type::Empty {
+            map[coord].typ = elem::Type::Road;
+        }
+    }
+    map[from].connect(dir);
+    map[to].connect(dir.rev());
+}
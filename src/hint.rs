@@ -0,0 +1,82 @@
+use ::map;
+use ::elem;
+use ::solver;
+
+pub enum Hint {
+    /// The moves every car should make after its partial route. A car
+    /// with fewer moves than asked stops after them.
+    Moves(Vec<Vec<elem::Dir>>),
+    /// The partial routes cannot be completed since the given tick. Tick 0
+    /// means the level itself has no solution.
+    Unsolvable { tick: usize },
+    /// The solver ran out of budget before finding out.
+    GaveUp,
+}
+
+/// What the solver is asked to complete the routes with.
+struct Search<'a> {
+    map: &'a map::Map,
+    cars: &'a [elem::Car],
+    targets: Option<&'a [bool]>,
+    budget: Option<usize>,
+}
+
+/// Complete `routes`, the moves already decided for every car, with the
+/// solver and return the next `n` moves of every car. With `targets`, only
+/// solutions where the drop zones take cubes as chosen are looked for; with
+/// `budget`, each search gives up after that many states.
+pub fn hint(map: &map::Map, cars: &[elem::Car], routes: &[Vec<elem::Dir>], targets: Option<&[bool]>, n: usize, budget: Option<usize>) -> Hint {
+    let search = Search { map, cars, targets, budget };
+    search.hint(routes, n).unwrap_or(Hint::GaveUp)
+}
+
+impl<'a> Search<'a> {
+    /// The hint, or None if the budget ran out.
+    fn hint(&self, routes: &[Vec<elem::Dir>], n: usize) -> Option<Hint> {
+        if let Some(solution) = self.complete(routes)? {
+            return Some(Hint::Moves(routes.iter().enumerate().map(|(i, route)| {
+                solution.dirs.iter()
+                             .skip(route.len())
+                             .take(n)
+                             .map_while(|moves| moves[i])
+                             .collect()
+            }).collect()));
+        }
+
+        // Completing the routes truncated to more ticks is never easier, so
+        // look for the first tick that cannot be completed with a bisection.
+        let (mut ok, mut ko) = (0, routes.iter().map(|route| route.len()).max().unwrap_or(0));
+        if self.complete(&truncate(routes, 0))?.is_none() {
+            return Some(Hint::Unsolvable { tick: 0 });
+        }
+        while ko - ok > 1 {
+            let mid = (ok + ko) / 2;
+            if self.complete(&truncate(routes, mid))?.is_some() {
+                ok = mid;
+            } else {
+                ko = mid;
+            }
+        }
+        Some(Hint::Unsolvable { tick: ko })
+    }
+
+    /// A solution following `routes`, if any, or None if the budget ran out.
+    fn complete(&self, routes: &[Vec<elem::Dir>]) -> Option<Option<solver::Solution>> {
+        let mut s = solver::Solver::with_prefix(self.map.clone(), self.cars.to_vec(), routes.to_vec());
+        if let Some(targets) = self.targets {
+            s.set_targets(targets.to_vec());
+        }
+        if let Some(budget) = self.budget {
+            s.set_budget(budget);
+        }
+        let solution = s.solve();
+        if solution.is_none() && s.exhausted() {
+            return None;
+        }
+        Some(solution)
+    }
+}
+
+fn truncate(routes: &[Vec<elem::Dir>], ticks: usize) -> Vec<Vec<elem::Dir>> {
+    routes.iter().map(|route| route.iter().take(ticks).cloned().collect()).collect()
+}
@@ -0,0 +1,115 @@
+use std::fmt::Write;
+use ::map;
+use ::elem;
+use ::solver;
+use ::svg;
+use ::report;
+
+/// Render a standalone HTML page replaying `solution` tick by tick. The map
+/// is the SVG export; every tick only records the cells that changed and
+/// where the cars are, which a small inline script applies on demand.
+pub fn render(map: &map::Map, cars: &[elem::Car], solution: &solver::Solution) -> String {
+    let timeline = solution.timeline(map, cars);
+
+    let mut data = String::from("[");
+    for (tick, state) in timeline.iter().enumerate() {
+        if tick > 0 {
+            data.push(',');
+        }
+        data.push_str("{\"cars\":[");
+        for (i, car) in state.cars.iter().enumerate() {
+            if i > 0 {
+                data.push(',');
+            }
+            let (x, y) = svg::center(car.coord);
+            let (x0, y0) = svg::center(cars[i].coord);
+            let cubes = car.cubes.iter().filter_map(|cube| match *cube {
+                elem::Type::Cube(c) => Some(format!("\"{}\"", svg::hex(c))),
+                _ => None,
+            }).collect::<Vec<_>>().join(",");
+            write!(data, "{{\"dx\":{},\"dy\":{},\"cubes\":[{}]}}",
+                   x as isize - x0 as isize, y as isize - y0 as isize, cubes).unwrap();
+        }
+        data.push_str("],\"cells\":[");
+        let changes: Vec<String> = state.changes.iter().map(|&((x, y), typ)| {
+            format!("[\"cell-{}-{}\",{}]", x, y, report::json_string(&svg::cell(typ, (x, y), false)))
+        }).collect();
+        data.push_str(&changes.join(","));
+        data.push_str("]}");
+    }
+    data.push(']');
+
+    let mut legend = String::new();
+    for (i, car) in cars.iter().enumerate() {
+        write!(legend, "<li><span class=\"swatch\" style=\"background:{}\"></span> truck {}: <span id=\"cargo-{}\"></span></li>",
+               svg::hex(car.color), i, i).unwrap();
+    }
+
+    format!("<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>RGB Express solution</title>
+<style>
+body {{ font-family: sans-serif; margin: 1em; }}
+#controls {{ margin: 1em 0; }}
+#controls input[type=range] {{ width: 20em; vertical-align: middle; }}
+ul {{ list-style: none; padding: 0; }}
+.swatch {{ display: inline-block; width: 1em; height: 1em; border: 1px solid #333; vertical-align: middle; }}
+.cube {{ display: inline-block; width: 0.8em; height: 0.8em; margin-right: 2px; border: 1px solid #333; }}
+</style>
+</head>
+<body>
+{svg}<div id=\"controls\">
+<button id=\"play\">play</button>
+<input id=\"scrub\" type=\"range\" min=\"0\" max=\"{last}\" value=\"0\">
+<span id=\"tick\"></span>
+</div>
+<ul>{legend}</ul>
+<script>
+var frames = {data};
+var initial = {{}};
+frames.forEach(function (f) {{
+  f.cells.forEach(function (c) {{
+    if (!(c[0] in initial)) initial[c[0]] = document.getElementById(c[0]).innerHTML;
+  }});
+}});
+var scrub = document.getElementById('scrub');
+var play = document.getElementById('play');
+var timer = null;
+function show(t) {{
+  for (var id in initial) document.getElementById(id).innerHTML = initial[id];
+  for (var i = 1; i <= t; i++) {{
+    frames[i].cells.forEach(function (c) {{ document.getElementById(c[0]).innerHTML = c[1]; }});
+  }}
+  frames[t].cars.forEach(function (car, i) {{
+    document.getElementById('truck-' + i).setAttribute('transform', 'translate(' + car.dx + ',' + car.dy + ')');
+    document.getElementById('cargo-' + i).innerHTML = car.cubes.map(function (c) {{
+      return '<span class=\"cube\" style=\"background:' + c + '\"></span>';
+    }}).join('');
+  }});
+  scrub.value = t;
+  document.getElementById('tick').textContent = 'tick ' + t + ' / ' + (frames.length - 1);
+}}
+function stop() {{
+  clearInterval(timer);
+  timer = null;
+  play.textContent = 'play';
+}}
+play.onclick = function () {{
+  if (timer) return stop();
+  if (+scrub.value == frames.length - 1) show(0);
+  play.textContent = 'pause';
+  timer = setInterval(function () {{
+    var t = +scrub.value + 1;
+    if (t >= frames.length) return stop();
+    show(t);
+  }}, 500);
+}};
+scrub.oninput = function () {{ stop(); show(+scrub.value); }};
+show(0);
+</script>
+</body>
+</html>
+", svg = svg::render(map, cars, Some(solution)), last = timeline.len() - 1, legend = legend, data = data)
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+use std::fs;
+use std::path;
+use std::str;
+use ::map;
+use ::elem;
+
+/// A map and the cars starting on it, as stored in level files:
+///
+/// ```text
+/// # comments and blank lines are ignored before the map
+/// car: 0 0 red
+/// car: 6 0 blue
+/// map:
+/// x--r--R  B--b--x--x
+/// ```
+///
+/// The header holds one `key: value` per line and ends with `map:`, after
+/// which everything is the map in the syntax read by `Map::from_str`.
+pub struct Level {
+    pub map: map::Map,
+    pub cars: Vec<elem::Car>,
+    /// Header entries not understood by this module, in order.
+    pub header: Vec<(String, String)>,
+}
+
+pub use ::map::ParseError;
+
+impl Level {
+    pub fn new(map: map::Map, cars: Vec<elem::Car>) -> Level {
+        Level {
+            map,
+            cars,
+            header: vec![],
+        }
+    }
+
+    pub fn load(path: &path::Path) -> Result<Level, String> {
+        let s = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        s.parse::<Level>().map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &path::Path) -> Result<(), String> {
+        fs::write(path, self.to_string()).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+}
+
+impl str::FromStr for Level {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Level, ParseError> {
+        let mut cars = vec![];
+        let mut header = vec![];
+        let mut lines = s.lines().enumerate();
+        for (n, line) in lines.by_ref() {
+            let error = |message: &str| ParseError { line: n + 1, message: message.to_string() };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = match line.find(':') {
+                Some(i) => (line[..i].trim(), line[i + 1..].trim()),
+                None => return Err(error("expected `key: value`")),
+            };
+            match key {
+                "map" => {
+                    let map: Vec<&str> = lines.map(|(_, line)| line).collect();
+                    let map: map::Map = map.join("\n").parse().map_err(|e: ParseError| ParseError {
+                        line: e.line + n + 1,
+                        message: e.message,
+                    })?;
+                    if let Some(car) = cars.iter().find(|car: &&elem::Car| car.coord.0 >= map.width || car.coord.1 >= map.height) {
+                        return Err(ParseError {
+                            line: n + 1,
+                            message: format!("car at ({}, {}) is outside the map", car.coord.0, car.coord.1),
+                        });
+                    }
+                    return Ok(Level { map, cars, header });
+                },
+                "car" => {
+                    let fields: Vec<&str> = value.split_whitespace().collect();
+                    if fields.len() != 3 {
+                        return Err(error("expected `car: X Y COLOR`"));
+                    }
+                    let x = fields[0].parse().map_err(|_| error("invalid car abscissa"))?;
+                    let y = fields[1].parse().map_err(|_| error("invalid car ordinate"))?;
+                    let color = elem::Color::from_name(fields[2]).ok_or_else(|| error("unknown car color"))?;
+                    cars.push(elem::Car::new(x, y, color));
+                },
+                _ => header.push((key.to_string(), value.to_string())),
+            }
+        }
+        Err(ParseError { line: s.lines().count(), message: "missing `map:`".to_string() })
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (key, value) in self.header.iter() {
+            writeln!(fmt, "{}: {}", key, value)?;
+        }
+        for car in self.cars.iter() {
+            writeln!(fmt, "car: {} {} {}", car.coord.0, car.coord.1, car.color.name())?;
+        }
+        writeln!(fmt, "map:")?;
+        // Keep the empty rows cars stand on.
+        let rows = self.cars.iter().map(|car| car.coord.1 + 1).max().unwrap_or(1);
+        write!(fmt, "{}", self.map.to_text_rows(rows))
+    }
+}
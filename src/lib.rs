@@ -0,0 +1,28 @@
+pub mod vec2d;
+pub mod elem;
+pub mod map;
+pub mod solver;
+pub mod report;
+pub mod render;
+pub mod svg;
+pub mod scenario;
+pub mod replay;
+pub mod html;
+pub mod play;
+pub mod hint;
+pub mod level;
+pub mod term;
+pub mod edit;
+pub mod rng;
+pub mod generate;
+pub mod difficulty;
+pub mod batch;
+pub mod bench;
+pub mod fuzz;
+pub mod transform;
+pub mod canon;
+pub mod cache;
+pub mod solution;
+pub mod route;
+pub mod event;
+pub mod game;
@@ -1,11 +1,17 @@
-mod vec2d;
-mod elem;
-mod map;
-mod solver;
-
-struct Scenario {
-    map: &'static str,
-    cars: Vec<elem::Car>,
+extern crate rgb_express_solver;
+
+use std::env;
+use std::fs;
+use std::path;
+use std::process;
+use std::time;
+use rgb_express_solver::{vec2d, elem, map, solver, report, render, svg, scenario, replay, html, play, level, edit, generate, difficulty, batch, bench, canon, cache, solution, route, hint, event};
+
+#[derive(Copy, Clone, PartialEq)]
+enum Format {
+    Text,
+    Json,
+    Csv,
 }
 
 struct Symbology {
@@ -14,7 +20,655 @@ struct Symbology {
     help: &'static str,
 }
 
+fn usage() -> ! {
+    eprintln!("usage: rgb-express-solver [--format text|json|csv] [--render RENDERER] [--svg DIR]");
+    eprintln!("       rgb-express-solver replay LEVEL [--delay MS | --step] [--render RENDERER]");
+    eprintln!("       rgb-express-solver render LEVEL --html|--svg [-o FILE]");
+    eprintln!("       rgb-express-solver solution LEVEL [-o FILE]");
+    eprintln!("       rgb-express-solver verify LEVEL SOLUTION | verify LEVEL --route ROUTE... [--targets 01...]");
+    eprintln!("       rgb-express-solver hint LEVEL [--route ROUTE]... [-n MOVES]");
+    eprintln!("       rgb-express-solver narrate LEVEL [--json]");
+    eprintln!("       rgb-express-solver play LEVEL [--render RENDERER]");
+    eprintln!("       rgb-express-solver edit FILE [--from LEVEL | --size WxH] [--render RENDERER]");
+    eprintln!("       rgb-express-solver generate [--size WxH] [--trucks N] [--colors N] [--cubes N] [--buttons] [--drop-zones] [--seed N] [--count N] [-o DIR]");
+    eprintln!("       rgb-express-solver difficulty LEVEL...");
+    eprintln!("       rgb-express-solver solve-all DIR [--budget NODES] [--csv FILE] [--json FILE] [--no-cache]");
+    eprintln!("       rgb-express-solver dedupe FILE|DIR...");
+    eprintln!("       rgb-express-solver cache stats|clear");
+    eprintln!("       rgb-express-solver bench [--runs N] [--budget NODES] [--strategy dfs|bfs]... [--save FILE] [--baseline FILE] [--tolerance PCT]");
+    eprintln!();
+    eprintln!("LEVEL is a built-in scenario number or the path of a level file.");
+    eprintln!("RENDERER is one of auto, ansi, ascii or accessible.");
+    eprintln!("ROUTE is the moves of a car in order, e.g. `2R 3D 1.` or `2→ 3↓`, `.` for waiting.");
+    eprintln!("Solutions are cached in $RGB_EXPRESS_CACHE, or {}.", cache::DEFAULT_DIR);
+    process::exit(1);
+}
+
 fn main() {
+    let mut args = env::args().skip(1).peekable();
+    match args.peek().map(|s| s.as_str()) {
+        Some("replay") => {
+            args.next();
+            replay(args);
+        },
+        Some("render") => {
+            args.next();
+            render(args);
+        },
+        Some("solution") => {
+            args.next();
+            write_solution(args);
+        },
+        Some("verify") => {
+            args.next();
+            verify(args);
+        },
+        Some("hint") => {
+            args.next();
+            hint(args);
+        },
+        Some("narrate") => {
+            args.next();
+            narrate(args);
+        },
+        Some("play") => {
+            args.next();
+            play(args);
+        },
+        Some("edit") => {
+            args.next();
+            edit(args);
+        },
+        Some("generate") => {
+            args.next();
+            generate(args);
+        },
+        Some("difficulty") => {
+            args.next();
+            difficulty(args);
+        },
+        Some("solve-all") => {
+            args.next();
+            solve_all(args);
+        },
+        Some("dedupe") => {
+            args.next();
+            dedupe(args);
+        },
+        Some("cache") => {
+            args.next();
+            cache(args);
+        },
+        Some("bench") => {
+            args.next();
+            let options = bench::Options::from_args(args).unwrap_or_else(|_| usage());
+            match bench::run(&options) {
+                Ok(0) => {},
+                Ok(_) => process::exit(1),
+                Err(e) => {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                },
+            }
+        },
+        _ => solve(args),
+    }
+}
+
+fn renderer_arg(arg: Option<String>) -> Box<dyn render::Renderer> {
+    match arg.as_deref().and_then(render::from_name) {
+        Some(r) => r,
+        None => usage(),
+    }
+}
+
+fn solve<I: Iterator<Item = String>>(mut args: I) {
+    let mut format = Format::Text;
+    let mut renderer = render::auto();
+    let mut svg_dir = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => format = match args.next().as_deref() {
+                Some("text") => Format::Text,
+                Some("json") => Format::Json,
+                Some("csv")  => Format::Csv,
+                _ => usage(),
+            },
+            "--render" => renderer = renderer_arg(args.next()),
+            "--svg" => svg_dir = Some(path::PathBuf::from(args.next().unwrap_or_else(|| usage()))),
+            _ => usage(),
+        }
+    }
+    if let Some(ref dir) = svg_dir {
+        if let Err(e) = fs::create_dir_all(dir) {
+            eprintln!("cannot create {}: {}", dir.display(), e);
+            process::exit(1);
+        }
+    }
+
+    if format == Format::Text {
+        print_symbology(&*renderer);
+    }
+
+    let scenarii = scenario::all();
+
+    match format {
+        Format::Json => println!("["),
+        Format::Csv  => println!("{}", report::CSV_HEADER),
+        Format::Text => {},
+    }
+    for (n, scenario) in scenarii.into_iter().enumerate() {
+        let m: map::Map = scenario.map.parse().expect("built-in scenario");
+        let mut map = m.clone();
+        if format == Format::Text {
+            println!("INPUT:\n{}", m.render(&*renderer));
+        }
+        let mut s = solver::Solver::new(m, scenario.cars.to_vec());
+        let solution = s.solve();
+        if let Some(ref dir) = svg_dir {
+            let file = dir.join(format!("scenario-{}.svg", n));
+            if let Err(e) = fs::write(&file, svg::render(&map, &scenario.cars, solution.as_ref())) {
+                eprintln!("cannot write {}: {}", file.display(), e);
+            }
+        }
+        match format {
+            Format::Text => print_text(&mut map, &scenario.cars, solution.as_ref(), &*renderer),
+            Format::Json => println!("{}{{\"scenario\":{},\"solution\":{}}}",
+                                     if n > 0 { "," } else { "" }, n,
+                                     solution.map(|solution| report::Report::new(&map, &scenario.cars, &solution).to_json())
+                                             .unwrap_or_else(|| "null".to_string())),
+            Format::Csv  => if let Some(solution) = solution {
+                print!("{}", report::Report::new(&map, &scenario.cars, &solution).to_csv(&n.to_string()));
+            },
+        }
+    }
+    if format == Format::Json {
+        println!("]");
+    }
+}
+
+/// A built-in scenario number or the path of a level file.
+fn level_arg(arg: &str) -> level::Level {
+    if let Some(scenario) = arg.parse::<usize>().ok().and_then(|n| scenario::all().into_iter().nth(n)) {
+        return scenario.level();
+    }
+    load_level(path::Path::new(arg))
+}
+
+fn load_level(path: &path::Path) -> level::Level {
+    match level::Level::load(path) {
+        Ok(level) => level,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+    }
+}
+
+fn replay<I: Iterator<Item = String>>(mut args: I) {
+    let mut renderer = render::auto();
+    let mut pace = replay::Pace::Delay(time::Duration::from_millis(500));
+    let mut level = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--delay" => pace = match args.next().and_then(|ms| ms.parse().ok()) {
+                Some(ms) => replay::Pace::Delay(time::Duration::from_millis(ms)),
+                None => usage(),
+            },
+            "--step" => pace = replay::Pace::Step,
+            "--render" => renderer = renderer_arg(args.next()),
+            _ => level = Some(level_arg(&arg)),
+        }
+    }
+    let level = level.unwrap_or_else(|| usage());
+
+    let mut s = solver::Solver::new(level.map.clone(), level.cars.to_vec());
+    match s.solve() {
+        Some(solution) => replay::replay(&level.map, &level.cars, &solution, &*renderer, pace),
+        None => {
+            eprintln!("no solution found");
+            process::exit(1);
+        },
+    }
+}
+
+fn render<I: Iterator<Item = String>>(mut args: I) {
+    let mut html = None;
+    let mut output = None;
+    let mut level = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--html" => html = Some(true),
+            "--svg" => html = Some(false),
+            "-o" => output = Some(args.next().unwrap_or_else(|| usage())),
+            _ => level = Some(level_arg(&arg)),
+        }
+    }
+    let (level, html) = match (level, html) {
+        (Some(level), Some(html)) => (level, html),
+        _ => usage(),
+    };
+
+    let mut s = solver::Solver::new(level.map.clone(), level.cars.to_vec());
+    let solution = s.solve();
+    let page = match solution {
+        Some(ref solution) if html => html::render(&level.map, &level.cars, solution),
+        None if html => {
+            eprintln!("no solution found");
+            process::exit(1);
+        },
+        _ => svg::render(&level.map, &level.cars, solution.as_ref()),
+    };
+    match output {
+        Some(file) => if let Err(e) = fs::write(&file, page) {
+            eprintln!("cannot write {}: {}", file, e);
+            process::exit(1);
+        },
+        None => print!("{}", page),
+    }
+}
+
+fn write_solution<I: Iterator<Item = String>>(mut args: I) {
+    let mut output = None;
+    let mut level = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" => output = Some(args.next().unwrap_or_else(|| usage())),
+            _ => level = Some(level_arg(&arg)),
+        }
+    }
+    let level = level.unwrap_or_else(|| usage());
+
+    let mut s = solver::Solver::new(level.map.clone(), level.cars.to_vec());
+    let solution = match cache::Cache::from_env().solve(&mut s, &level.map, &level.cars) {
+        Some(solution) => solution::SolutionFile::new(&level.map, &level.cars, solution),
+        None => {
+            eprintln!("no solution found");
+            process::exit(1);
+        },
+    };
+    match output {
+        Some(file) => if let Err(e) = solution.save(path::Path::new(&file)) {
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+        None => print!("{}", solution),
+    }
+}
+
+fn verify<I: Iterator<Item = String>>(mut args: I) {
+    let mut level = None;
+    let mut file = None;
+    let mut routes = vec![];
+    let mut targets = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--route" => routes.push(route_arg(args.next())),
+            "--targets" => targets = Some(targets_arg(args.next())),
+            _ if level.is_none() => level = Some(level_arg(&arg)),
+            _ if file.is_none() => file = Some(arg),
+            _ => usage(),
+        }
+    }
+    let level = level.unwrap_or_else(|| usage());
+
+    let (name, saved) = match (file, routes.is_empty()) {
+        (Some(file), true) => match solution::SolutionFile::load(path::Path::new(&file)) {
+            Ok(saved) => (file, saved),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            },
+        },
+        (None, false) => {
+            let zones = level.map.iter().filter(|e| matches!(e.typ, elem::Type::DropOn | elem::Type::DropOff)).count();
+            let solution = route::solution(&routes, targets.unwrap_or_else(|| vec![false; zones]));
+            ("routes".to_string(), solution::SolutionFile::new(&level.map, &level.cars, solution))
+        },
+        _ => usage(),
+    };
+    match saved.verify(&level.map, &level.cars) {
+        Ok(()) => println!("{}: solved in {} ticks", name, saved.solution.dirs.len()),
+        Err(e) => {
+            eprintln!("{}: {}", name, e);
+            process::exit(1);
+        },
+    }
+}
+
+fn hint<I: Iterator<Item = String>>(mut args: I) {
+    let mut level = None;
+    let mut routes = vec![];
+    let mut n = 1;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--route" => routes.push(route_arg(args.next())),
+            "-n" => n = number_arg(args.next()),
+            _ => level = Some(level_arg(&arg)),
+        }
+    }
+    let level = level.unwrap_or_else(|| usage());
+    if routes.len() > level.cars.len() {
+        eprintln!("{} route(s) for {} car(s)", routes.len(), level.cars.len());
+        process::exit(1);
+    }
+    let mut moves: Vec<Vec<elem::Dir>> = routes.iter().enumerate().map(|(i, route)| route.moves().unwrap_or_else(|| {
+        eprintln!("car {} moves after waiting", i);
+        process::exit(1);
+    })).collect();
+    moves.resize(level.cars.len(), vec![]);
+
+    match hint::hint(&level.map, &level.cars, &moves, None, n, None) {
+        hint::Hint::Moves(next) => for (i, (car, next)) in level.cars.iter().zip(next).enumerate() {
+            println!("{} ({}, {}): {}", i, car.coord.0, car.coord.1, route::Route(next.into_iter().map(Some).collect()));
+        },
+        hint::Hint::Unsolvable { tick: 0 } => {
+            println!("this level has no solution");
+            process::exit(1);
+        },
+        hint::Hint::Unsolvable { tick } => {
+            println!("no solution since tick {}", tick);
+            process::exit(1);
+        },
+        hint::Hint::GaveUp => {
+            println!("the solver gave up");
+            process::exit(1);
+        },
+    }
+}
+
+fn narrate<I: Iterator<Item = String>>(args: I) {
+    let mut json = false;
+    let mut level = None;
+    for arg in args {
+        match arg.as_str() {
+            "--json" => json = true,
+            _ => level = Some(level_arg(&arg)),
+        }
+    }
+    let level = level.unwrap_or_else(|| usage());
+
+    let mut s = solver::Solver::new(level.map.clone(), level.cars.to_vec());
+    let solution = cache::Cache::from_env().solve(&mut s, &level.map, &level.cars).unwrap_or_else(|| {
+        eprintln!("no solution found");
+        process::exit(1);
+    });
+    let ticks = solver::events(&level.map, &level.cars, &solution);
+    if json {
+        let ticks: Vec<String> = ticks.iter().enumerate().map(|(t, events)| {
+            let events: Vec<String> = events.iter().map(event::Event::to_json).collect();
+            format!("{{\"tick\":{},\"events\":[{}]}}", t + 1, events.join(","))
+        }).collect();
+        println!("[{}]", ticks.join(","));
+        return;
+    }
+    for (t, events) in ticks.iter().enumerate() {
+        println!("tick {}:", t + 1);
+        for e in events {
+            println!("  {}", e);
+        }
+    }
+}
+
+fn route_arg(arg: Option<String>) -> route::Route {
+    match arg.unwrap_or_else(|| usage()).parse() {
+        Ok(route) => route,
+        Err(e) => {
+            eprintln!("invalid route: {}", e);
+            process::exit(1);
+        },
+    }
+}
+
+fn targets_arg(arg: Option<String>) -> Vec<bool> {
+    arg.unwrap_or_else(|| usage()).chars().map(|c| match c {
+        '0' => false,
+        '1' => true,
+        _ => usage(),
+    }).collect()
+}
+
+fn play<I: Iterator<Item = String>>(mut args: I) {
+    let mut renderer = render::auto();
+    let mut level = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--render" => renderer = renderer_arg(args.next()),
+            _ => level = Some(level_arg(&arg)),
+        }
+    }
+    let level = level.unwrap_or_else(|| usage());
+
+    if !play::play(&level.map, &level.cars, &*renderer) {
+        process::exit(1);
+    }
+}
+
+fn edit<I: Iterator<Item = String>>(mut args: I) {
+    let mut renderer = render::auto();
+    let mut file = None;
+    let mut level = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--render" => renderer = renderer_arg(args.next()),
+            "--from" => level = Some(level_arg(&args.next().unwrap_or_else(|| usage()))),
+            "--size" => {
+                let (w, h) = size_arg(args.next());
+                level = Some(level::Level::new(vec2d::Vec2D::new(w, h), vec![]));
+            },
+            _ => file = Some(path::PathBuf::from(arg)),
+        }
+    }
+    let file = file.unwrap_or_else(|| usage());
+    let level = match level {
+        Some(level) => level,
+        None if file.exists() => load_level(&file),
+        None => level::Level::new(vec2d::Vec2D::new(8, 8), vec![]),
+    };
+    edit::edit(level, &file, &*renderer);
+}
+
+/// A `WxH` size, both positive.
+fn size_arg(arg: Option<String>) -> (usize, usize) {
+    match arg.as_ref().and_then(|size| size.find('x').map(|i| (&size[..i], &size[i + 1..]))) {
+        Some((w, h)) => match (w.parse(), h.parse()) {
+            (Ok(w), Ok(h)) if w > 0 && h > 0 => (w, h),
+            _ => usage(),
+        },
+        None => usage(),
+    }
+}
+
+fn number_arg<T: std::str::FromStr>(arg: Option<String>) -> T {
+    arg.and_then(|n| n.parse().ok()).unwrap_or_else(|| usage())
+}
+
+fn generate<I: Iterator<Item = String>>(mut args: I) {
+    let mut params = generate::Params::default();
+    let mut count = 1;
+    let mut dir = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => {
+                let (w, h) = size_arg(args.next());
+                params.width = w;
+                params.height = h;
+            },
+            "--trucks" => params.trucks = number_arg(args.next()),
+            "--colors" => params.colors = number_arg(args.next()),
+            "--cubes" => params.cubes = number_arg(args.next()),
+            "--buttons" => params.buttons = true,
+            "--drop-zones" => params.drop_zones = true,
+            "--seed" => params.seed = number_arg(args.next()),
+            "--count" => count = number_arg(args.next()),
+            "-o" => dir = Some(path::PathBuf::from(args.next().unwrap_or_else(|| usage()))),
+            _ => usage(),
+        }
+    }
+    if let Some(ref dir) = dir {
+        if let Err(e) = fs::create_dir_all(dir) {
+            eprintln!("cannot create {}: {}", dir.display(), e);
+            process::exit(1);
+        }
+    }
+
+    let first = params.seed;
+    for seed in first..first + count {
+        params.seed = seed;
+        let level = match generate::generate(&params) {
+            Ok(level) => level,
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1);
+            },
+        };
+        match dir {
+            Some(ref dir) => {
+                let file = dir.join(format!("level-{}.txt", seed));
+                if let Err(e) = level.save(&file) {
+                    eprintln!("{}", e);
+                    process::exit(1);
+                }
+            },
+            None => println!("{}", level),
+        }
+    }
+}
+
+/// Rate every level and list them from the easiest to the hardest.
+fn difficulty<I: Iterator<Item = String>>(args: I) {
+    let mut rated: Vec<(String, difficulty::DifficultyReport)> = args.map(|arg| {
+        let report = difficulty::difficulty(&level_arg(&arg));
+        (arg, report)
+    }).collect();
+    if rated.is_empty() {
+        usage();
+    }
+    rated.sort_by(|a, b| a.1.score.total_cmp(&b.1.score));
+
+    println!("{:>6} {:>8} {:>9} {:>9} {:>8} {:>7} {:>5}  level", "score", "nodes", "branching", "solutions", "shortest", "toggles", "drops");
+    for (name, report) in rated {
+        println!("{:>6.1} {:>8} {:>9.2} {:>9} {:>8} {:>7} {:>5}  {}",
+                 report.score, report.nodes, report.branching,
+                 format!("{}{}", report.solutions, if report.complete { "" } else { "+" }),
+                 report.shortest.map_or("-".to_string(), |n| n.to_string()),
+                 report.toggles, report.drops, name);
+    }
+}
+
+fn dedupe<I: Iterator<Item = String>>(args: I) {
+    let files: Vec<path::PathBuf> = args.map(path::PathBuf::from).collect();
+    if files.is_empty() {
+        usage();
+    }
+    let (groups, errors) = match canon::group(&files) {
+        Ok(groups) => groups,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+    };
+    for e in errors.iter() {
+        eprintln!("{}", e);
+    }
+    let levels: usize = groups.iter().map(|g| g.files.len()).sum();
+    for g in groups.iter().filter(|g| g.files.len() > 1) {
+        println!("{:016x}", g.hash);
+        for file in g.files.iter() {
+            println!("  {}", file.display());
+        }
+    }
+    println!("{} level(s), {} distinct", levels, groups.len());
+    if !errors.is_empty() {
+        process::exit(1);
+    }
+}
+
+fn cache<I: Iterator<Item = String>>(mut args: I) {
+    let cache = cache::Cache::from_env();
+    let result = match (args.next().as_deref(), args.next()) {
+        (Some("stats"), None) => cache.stats().map(|stats| {
+            println!("{}: {} solution(s), {} byte(s), {} from older rules",
+                     cache.dir().display(), stats.entries, stats.bytes, stats.stale);
+        }),
+        (Some("clear"), None) => cache.clear().map(|n| println!("{}: removed {} solution(s)", cache.dir().display(), n)),
+        _ => usage(),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+fn solve_all<I: Iterator<Item = String>>(mut args: I) {
+    let mut budget = 1_000_000;
+    let mut csv = None;
+    let mut json = None;
+    let mut dir = None;
+    let mut cache = Some(cache::Cache::from_env());
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--budget" => budget = number_arg(args.next()),
+            "--no-cache" => cache = None,
+            "--csv" => csv = Some(args.next().unwrap_or_else(|| usage())),
+            "--json" => json = Some(args.next().unwrap_or_else(|| usage())),
+            _ => dir = Some(path::PathBuf::from(arg)),
+        }
+    }
+    let dir = dir.unwrap_or_else(|| usage());
+
+    let outcomes = match batch::solve_all(&dir, budget, cache.as_ref()) {
+        Ok(outcomes) => outcomes,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        },
+    };
+    println!("{:<24} {:<10} {:>10} {:>9} {:>5} {:>10}", "file", "status", "time (ms)", "nodes", "ticks", "expected");
+    for o in outcomes.iter() {
+        println!("{:<24} {:<10} {:>10.1} {:>9} {:>5} {:>10}{}{}",
+                 o.file, o.status.name(), batch::millis(o.time), o.nodes,
+                 o.ticks.map_or("-".to_string(), |n| n.to_string()),
+                 o.expected.map_or("-".to_string(), |e| e.to_string()),
+                 if o.cached { "  (cached)" } else { "" },
+                 if o.mismatch() { "  MISMATCH" } else { "" });
+    }
+    for o in outcomes.iter() {
+        if let batch::Status::Error(ref e) = o.status {
+            eprintln!("{}", e);
+        }
+    }
+    let solved = outcomes.iter().filter(|o| o.status == batch::Status::Solved).count();
+    let mismatches = outcomes.iter().filter(|o| o.mismatch()).count();
+    println!("\n{} level(s), {} solved, {} mismatch(es)", outcomes.len(), solved, mismatches);
+
+    for (file, contents) in [(csv, batch::to_csv(&outcomes)), (json, batch::to_json(&outcomes))] {
+        if let Some(file) = file {
+            if let Err(e) = fs::write(&file, contents) {
+                eprintln!("cannot write {}: {}", file, e);
+                process::exit(1);
+            }
+        }
+    }
+    if mismatches > 0 || outcomes.iter().any(|o| matches!(o.status, batch::Status::Error(_))) {
+        process::exit(1);
+    }
+}
+
+fn print_text(map: &mut map::Map, cars: &[elem::Car], solution: Option<&solver::Solution>, r: &dyn render::Renderer) {
+    if let Some(solution) = solution {
+        println!("SOLUTION FOUND:");
+        map.output_solution(solution, cars, r);
+        for (i, car) in cars.iter().enumerate() {
+            println!("{} ({}, {}): {}", r.car(i, car), car.coord.0, car.coord.1, r.route(&route::Route::of(solution, i).trimmed()));
+        }
+    } else {
+        println!("NO SOLUTION FOUND");
+    }
+    println!("\n\n\n");
+}
+
+fn print_symbology(r: &dyn render::Renderer) {
     println!("SYMBOLOGY:");
     for sym in vec![
         Symbology{input: 'x', extra: ' ', help: "road"},
@@ -44,372 +698,14 @@ fn main() {
         Symbology{input: '~', extra: 'c', help: "cream open brige"},
         Symbology{input: '#', extra: 'c', help: "cream closed button"},
     ] {
-        println!("{}{} => {}: {}",
+        let e = elem::Elem::from_char(sym.input, sym.extra);
+        println!("{}{} => {}{}: {}",
                     sym.input,
                     sym.extra,
-                    elem::Elem::from_char(sym.input, sym.extra),
+                    r.elem(&e),
+                    r.tag(&e).unwrap_or(' '),
                     sym.help,
                 );
     }
     println!("\n\n");
-
-    let scenarii = vec![
-        Scenario {
-            map: "\
-                x  r--R\n\
-                |  |   \n\
-                x--x   \
-            ",
-            cars: vec![
-                elem::Car::new(0, 0, elem::Color::Red),
-            ],
-        },
-        Scenario {
-            map: "\
-                x--B--x     x--B--x\n\
-                |     |     |     |\n\
-                x     x--B  x     x\n\
-                |           |     |\n\
-                x--x--x--x--x     x\n\
-                .                 |\n\
-                x--b--b--b--x--x--x\
-            ",
-            cars: vec![
-                elem::Car::new(0, 3, elem::Color::Blue),
-            ],
-        },
-        Scenario {
-            map: "\
-                R--x--x   \n\
-                .     |   \n\
-                .  x--x--x\n\
-                .  |  |  |\n\
-                .  r--x  x\n\
-                .        |\n\
-                x--x--x--x\n\
-            ",
-            cars: vec![
-                elem::Car::new(0, 3, elem::Color::Red),
-            ],
-        },
-        Scenario {
-            map: "x--r--R  B--b--x--x",
-            cars: vec![
-                elem::Car::new(0, 0, elem::Color::Red),
-                elem::Car::new(6, 0, elem::Color::Blue),
-            ],
-        },
-        Scenario {
-            map: "\
-                x--r--x--x--x\n\
-                .  |     |   \n\
-                B--x--x  b   \n\
-                .  |  |  |   \n\
-                R--x  x--x   \n\
-            ",
-            cars: vec![
-                elem::Car::new(0, 0, elem::Color::Red),
-                elem::Car::new(4, 0, elem::Color::Blue),
-            ],
-        },
-        Scenario {
-            map: "\
-                .     R      \n\
-                .     |      \n\
-                .     r      \n\
-                .     |      \n\
-                x--x--x--x--x\n\
-                .     |      \n\
-                .     b      \n\
-                .     |      \n\
-                .     B      \n\
-            ",
-            cars: vec![
-                elem::Car::new(0, 2, elem::Color::Red),
-                elem::Car::new(4, 2, elem::Color::Blue),
-            ],
-        },
-        Scenario {
-            map: "\
-                .  x--R--x--G--x   \n\
-                .  |     |     |   \n\
-                .  x--x--r--x--x   \n\
-                .  |     |     |   \n\
-                .  x--x--g--x--x   \n\
-                .  |     |     |   \n\
-                .  x     x     x   \n\
-                .  |     |     |   \n\
-                .  x     x     x   \n\
-                .  |     |     |   \n\
-                x--x--x--x--x--x--x\n\
-            ",
-            cars: vec![
-                elem::Car::new(0, 5, elem::Color::Red),
-                elem::Car::new(6, 5, elem::Color::Green),
-            ],
-        },
-        Scenario {
-            map: "\
-                .  x--x--r  x--x--x   \n\
-                .  |     |  |     |   \n\
-                R--x     x--x     x--x\n\
-                .  |     |  |     |   \n\
-                .  x--x--x  y--x--x   \n\
-                .     |        |      \n\
-                .  x--x--Y  x--x--x   \n\
-                .  |     |  |     |   \n\
-                .  x     x--x     x   \n\
-                .  |     |  |     |   \n\
-                .  x--x--x  x--x--x   \n\
-                .     |               \n\
-                .     x
-            ",
-            cars: vec![
-                elem::Car::new(2, 6, elem::Color::Red),
-                elem::Car::new(7, 1, elem::Color::Yellow),
-            ],
-        },
-        Scenario {
-            map: "\
-             x--x--x--x--x--x--x--x--x\n\
-             |           |           |\n\
-             x     b--B--x--x--b     x\n\
-             |     |     |     |     |\n\
-             x     x     x     x     x\n\
-             |     |     |     |     |\n\
-             x--B--x--x--x--x--x--x--b\n\
-             |     |     |     |     |\n\
-             x     x     x     x     x\n\
-             |     |     |     |     |\n\
-             x     x--x--x--B--x     x\n\
-             |           |           |\n\
-             x--x--x--x--x--x--x--x--x\
-            ",
-            cars: vec![
-                elem::Car::new(4, 3, elem::Color::Blue),
-            ],
-        },
-        Scenario {
-            map: "\
-            x--r--R      \n\
-            .            \n\
-            x--b--x--B   \n\
-            .            \n\
-            x--y--x--x--Y\
-            ",
-            cars: vec![
-                elem::Car::new(0, 0, elem::Color::Red),
-                elem::Car::new(0, 1, elem::Color::Blue),
-                elem::Car::new(0, 2, elem::Color::Yellow),
-            ],
-        },
-        Scenario {
-            map: "\
-            x--x--x--x--x--x--x--x\n\
-            |           |        |\n\
-            x           x        x\n\
-            |           |        |\n\
-            x--R--x--x--r--x--R--x\n\
-            |     |     |        |\n\
-            x     x     x        x\n\
-            |     |     |        |\n\
-            x     x     x--x--R--x\n\
-            |     |     |        |\n\
-            r--x--r     r        x\n\
-            |     |     |        |\n\
-            x     x     x--x--R--x\n\
-            |     |     |        |\n\
-            x     x     x        x\n\
-            |     |     |        |\n\
-            x--x--x--x--x--x--x--x\n\
-            .           |         \n\
-            .           x         \
-            ",
-            cars: vec![
-                elem::Car::new(4, 9, elem::Color::Red),
-            ],
-        },
-        Scenario {
-            map: "\
-            R--x--Y--x--B--x--x--x--x--x--x\n\
-            .                             |\n\
-            .                             x\n\
-            .                             |\n\
-            x--x--x--r--x--y--x--b--x--x--x\
-            ",
-            cars: vec![
-                elem::Car::new(0, 2, elem::Color::White),
-            ],
-        },
-        Scenario {
-            map: "\
-            .        x--x--~v-x--x         \n\
-            .        |           |         \n\
-            x--r--^v-x--x--#v-x--x--x--x--R\
-            ",
-            cars: vec![
-                elem::Car::new(0, 1, elem::Color::Red),
-            ],
-        },
-        Scenario {
-            map: "\
-            .           R            \n\
-            .           |            \n\
-            .           x            \n\
-            .           |            \n\
-            .           ~v           \n\
-            .           |            \n\
-            x--x--x--x--x--x--x--x--x\n\
-            |           |           |\n\
-            x           x           x\n\
-            |           |           |\n\
-            ^v          ~v          r\n\
-            |           |           |\n\
-            x           x           x\n\
-            |           |           |\n\
-            x--x--x--x--x--x--x--x--x\n\
-            .           |            \n\
-            .           x            \
-            ",
-            cars: vec![
-                elem::Car::new(4, 8, elem::Color::Red),
-            ],
-        },
-        Scenario {
-            map: "\
-            x--r--^p-x--R\n\
-            .            \n\
-            x--b--#p-x--B\n\
-            ",
-            cars: vec![
-                elem::Car::new(0, 1, elem::Color::Blue),
-                elem::Car::new(0, 0, elem::Color::Red),
-            ],
-        },
-        Scenario {
-            map: "\
-            x--r--^p-x--^o-vp-x--#p-~o-x--x--R\
-            ",
-            cars: vec![
-                elem::Car::new(0, 0, elem::Color::Red),
-            ],
-        },
-        Scenario {
-            map: "\
-            x--vo-x--x--x     x--x--x--x--x \n\
-            |     |     |     |           | \n\
-            x     y     x--#o-x           x \n\
-            |     |     |     |           | \n\
-            x--^o-x--x--x     x--x--G--x--x \n\
-            |     |     |     |     |     | \n\
-            ~o    ~o    ~o    ~v    ~o    ~v\n\
-            |     |     |     |     |     | \n\
-            x--^o-x--^o-x     x     x--vv-x \n\
-            |     |     |     |     |     | \n\
-            x     x     x--~o-x     ^o    g \n\
-            |     |     |     |     |     | \n\
-            x--Y--^o-x--x     x--x--r--x--x \n\
-            |     |     |     |     |     | \n\
-            #v    ~o    ~v    ~v    #o    #v \n\
-            |     |     |     |     |     | \n\
-            x--^v-x--x--x     x--x--x--x--x \n\
-            |     |     |     |           | \n\
-            x     x     x--~o-x           x \n\
-            |     |     |     |           | \n\
-            x--x--x--vv-x     x--x--R--x--x \
-            ",
-            cars: vec![
-                elem::Car::new(2, 9, elem::Color::White),
-            ],
-        },
-        Scenario {
-            map: "\
-            .        x--x--x     x--x\n\
-            .        |     |     |  |\n\
-            x--r--b--x  B--O--x  x  x\n\
-            .        |     |  |  |  |\n\
-            .        R--x--x  x--x  x\
-            ",
-            cars: vec![
-                elem::Car::new(0, 1, elem::Color::Red),
-                elem::Car::new(8, 2, elem::Color::Blue),
-            ],
-        },
-        Scenario {
-            map: "\
-            x                             x\n\
-            |                             |\n\
-            y                             r\n\
-            |                             |\n\
-            x     R--x--G--x--B--x--Y     x\n\
-            |     |  |  |  |  |  |  |     |\n\
-            x     x--O--O--O--O--O--x     x\n\
-            |     |  |  |  |  |  |  |     |\n\
-            x--x--O--O--O--O--O--O--O--x--x\n\
-            |  |  |  |  |  |  |  |  |  |  |\n\
-            x--O--O--O--O--O--O--O--O--O--x\n\
-            |  |  |  |  |  |  |  |  |  |  |\n\
-            x--x--O--O--O--O--O--O--O--x--x\n\
-            |     |  |  |  |  |  |  |     |\n\
-            x     x--O--O--O--O--O--x     x\n\
-            |     |  |  |  |  |  |  |     |\n\
-            x     x--x--x--x--x--x--x     x\n\
-            |                             |\n\
-            b                             g\n\
-            |                             |\n\
-            x                             x\
-            ",
-            cars: vec![
-                elem::Car::new(0, 0, elem::Color::Red),
-                elem::Car::new(0, 10, elem::Color::Green),
-                elem::Car::new(10, 0, elem::Color::Yellow),
-                elem::Car::new(10, 10, elem::Color::Blue),
-            ],
-        },
-    ];
-
-    for scenario in scenarii {
-        let m = map::Map::from_str(scenario.map);
-        let mut map = m.clone();
-        println!("INPUT:\n{}", m);
-        let mut s = solver::Solver::new(m, scenario.cars.to_vec());
-        if let Option::Some(solution) = s.solve() {
-            println!("SOLUTION FOUND:");
-            map.output_solution(&solution, &scenario.cars);
-            for (i, car) in scenario.cars.iter().enumerate() {
-                let car_moves: Vec<Option<elem::Dir>> = solution.dirs.iter().map(|moves| moves[i]).collect();
-                let car_moves = group(car_moves);
-                println!("{} ({}, {}): {}",
-                    car, car.coord.0, car.coord.1,
-                    car_moves.into_iter()
-                              .filter(|&(dir, _)| dir.is_some())
-                              .map(|(dir, n)| format!("{}{}", n, dir.unwrap()))
-                              .fold("".to_string(), |s, d| format!("{}{} ", s, d)),
-                );
-            }
-        } else {
-            println!("NO SOLUTION FOUND");
-        }
-        println!("\n\n\n");
-    }
-}
-
-fn group<T: Eq + Copy>(v: Vec<T>) -> Vec<(T, usize)> {
-    let mut r = vec![];
-    if v.len() == 0 {
-        return r;
-    }
-
-    let mut last = v.get(0).unwrap();
-    let mut n = 1;
-    for x in v.iter().skip(1) {
-        if *x != *last {
-            r.push((*last, n));
-            n = 0;
-            last = x;
-        }
-        n += 1;
-    }
-    r.push((*last, n));
-    r
 }
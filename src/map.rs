@@ -1,158 +1,341 @@
 use ::vec2d;
 use ::elem;
 use ::solver;
+use ::render;
+use ::transform;
+use ::event;
 use std::fmt;
 use std::result;
+use std::str;
 use std::collections::HashMap;
 
 pub type Map = vec2d::Vec2D<elem::Elem>;
 
-impl Map {
-    pub fn from_str(map: &str) -> Map { // FIXME: Option<Map, Err>
-        let size = map.len();
-        let width = map.find('\n').unwrap_or(size);
-        let height = size / width;
-
-        // FIXME ------------v
-        let rows = (height + 2) / 2;
-        let cols = (width + 2) / 3;
+/// Why a car cannot make a move.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum MoveError {
+    /// There is no road, or it has already been used.
+    NoRoad,
+    /// The road leads off the map.
+    OffMap,
+    /// Another car stands on the cell.
+    Collision,
+    /// The bridge is open.
+    OpenBridge,
+    /// The house already got its cube.
+    FullHouse,
+    /// Only a loaded truck can go through a house.
+    EmptyTruck,
+    /// The cube on top of the cargo does not match the house.
+    WrongColor,
+    /// The truck already carries three cubes.
+    Overloaded,
+    /// The car stopped on an earlier tick.
+    Stopped,
+    /// A bridge opened under the car.
+    Stranded,
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        write!(fmt, "{}", match *self {
+            MoveError::NoRoad     => "no road to follow",
+            MoveError::OffMap     => "the road leads off the map",
+            MoveError::Collision  => "another truck is in the way",
+            MoveError::OpenBridge => "the bridge is open",
+            MoveError::FullHouse  => "the house is already full",
+            MoveError::EmptyTruck => "the truck has nothing to deliver",
+            MoveError::WrongColor => "the top cube does not match the house",
+            MoveError::Overloaded => "the truck is full",
+            MoveError::Stopped    => "the truck has already stopped",
+            MoveError::Stranded   => "a bridge opened under the truck",
+        })
+    }
+}
+
+/// Why a text is not a map, `line` counting from 1.
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        write!(fmt, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl str::FromStr for Map {
+    type Err = ParseError;
+
+    fn from_str(map: &str) -> Result<Map, ParseError> {
+        let error = |r: usize, message: String| ParseError { line: r + 1, message };
+        let mut lines: Vec<Vec<char>> = map.split('\n')
+                                           .take_while(|row| !row.is_empty())
+                                           .map(|row| row.chars().collect())
+                                           .collect();
+        while lines.last().is_some_and(|row| row.iter().all(|c| c.is_whitespace())) {
+            lines.pop();
+        }
+        if lines.is_empty() {
+            return Err(error(0, "empty map".to_string()));
+        }
+
+        let rows = lines.len().div_ceil(2);
+        let cols = lines.iter().step_by(2).map(|row| row.len()).max().unwrap_or(0).div_ceil(3);
         let mut m = vec2d::Vec2D::new(cols, rows);
         // Collect stuff first
-        for (r, row) in map.split('\n')
-                            .take_while(|row| row.len() > 0)
-                            .enumerate()
-                            .filter(|&(r, _)| { r % 2 == 0 })
-                            .map(|(r, row)| { (r / 2, row) }) {
-            let mut row = row.to_string();
-            row.push(' ');
-            let extra_it = row.chars()
-                              .enumerate()
-                              .filter(|&(c, _)| { c % 3 == 1 })
-                              .map(|(_, col)| { col });
-            for ((c, x), extra) in row.chars()
-                               .enumerate()
-                               .filter(|&(c, _)| { c % 3 == 0 })
-                               .map(|(c, col)| { (c / 3, col) })
-                               .zip(extra_it) {
-                m[(c, r)] = elem::Elem::from_char(x, extra);
+        for (r, row) in lines.iter().enumerate().step_by(2) {
+            for (c, &x) in row.iter().enumerate().step_by(3) {
+                let extra = row.get(c + 1).cloned().unwrap_or(' ');
+                match x {
+                    'x' | 'O' | 'r' | 'R' | 'g' | 'G' | 'b' | 'B' | 'y' | 'Y' | '.' | ' ' => {},
+                    'v' | '^' | '~' | '#' if elem::Color::from_char(extra).is_some() => {},
+                    'v' | '^' | '~' | '#' => return Err(error(r, format!("unknown color `{}` at column {}", extra, c + 2))),
+                    _ => return Err(error(r, format!("unknown cell `{}` at column {}", x, c + 1))),
+                }
+                m[(c / 3, r / 2)] = elem::Elem::from_char(x, extra);
             }
         }
         // Connect
-        for (r, row) in map.split('\n')
-                            .take_while(|row| row.len() > 0)
-                            .enumerate() {
-            for (c, x) in row.chars().enumerate() {
+        for (r, row) in lines.iter().enumerate() {
+            for (c, &x) in row.iter().enumerate() {
                 if r % 2 == 0 && c % 3 == 2 && x == '-' {
+                    if c / 3 + 1 >= cols {
+                        return Err(error(r, format!("road out of the map at column {}", c + 1)));
+                    }
                     m[(c / 3    , r / 2)].connect(elem::Dir::Right);
                     m[(c / 3 + 1, r / 2)].connect(elem::Dir::Left);
                 } else if r % 2 == 1 && c % 3 == 0 && x == '|' {
+                    if r / 2 + 1 >= rows || c / 3 >= cols {
+                        return Err(error(r, format!("road out of the map at column {}", c + 1)));
+                    }
                     m[(c / 3, r / 2    )].connect(elem::Dir::Down);
                     m[(c / 3, r / 2 + 1)].connect(elem::Dir::Up);
                 }
             }
         }
-        m
+        Ok(m)
+    }
+}
+
+impl Map {
+    /// A quarter turn clockwise.
+    pub fn rotate90(&self) -> Map {
+        transform::Transform::Rotate90.map(self)
+    }
+
+    /// Swap left and right.
+    pub fn mirror_horizontal(&self) -> Map {
+        transform::Transform::MirrorHorizontal.map(self)
+    }
+
+    /// Swap top and bottom.
+    pub fn mirror_vertical(&self) -> Map {
+        transform::Transform::MirrorVertical.map(self)
+    }
+
+    /// Keep the cells of `rect`, cutting the roads leaving it.
+    pub fn crop(&self, rect: transform::Rect) -> Map {
+        transform::Transform::Crop(rect).map(self)
+    }
+
+    /// Surround with `n` empty rows and columns on every side.
+    pub fn pad(&self, n: usize) -> Map {
+        transform::Transform::Pad(n).map(self)
+    }
+
+    /// Write the map in the syntax read by `from_str`, dropping trailing
+    /// empty rows but the first. Targets are written as plain drop zones and
+    /// full houses as houses.
+    pub fn to_text(&self) -> String {
+        self.to_text_rows(1)
+    }
+
+    /// Like `to_text`, keeping at least `rows` rows.
+    pub fn to_text_rows(&self, rows: usize) -> String {
+        let rows = (0..self.height).rev()
+                                   .find(|&y| (0..self.width).any(|x| !matches!(self[(x, y)].typ, elem::Type::Empty)))
+                                   .map_or(0, |y| y + 1)
+                                   .max(rows.min(self.height));
+        let mut s = String::new();
+        for y in 0..rows {
+            if y > 0 {
+                for x in 0..self.width {
+                    s.push(if self[(x, y - 1)].connected(elem::Dir::Down) { '|' } else { ' ' });
+                    s.push(' ');
+                    if x + 1 < self.width {
+                        s.push(' ');
+                    }
+                }
+                s.push('\n');
+            }
+            for x in 0..self.width {
+                let e = &self[(x, y)];
+                let conn = if e.connected(elem::Dir::Right) { '-' } else { ' ' };
+                let (c, extra) = match e.typ {
+                    // A leading space would be lost by indented string literals.
+                    elem::Type::Empty if x == 0 => ('.', conn),
+                    elem::Type::Empty => (' ', conn),
+                    elem::Type::Road => ('x', conn),
+                    elem::Type::DropOn | elem::Type::DropOff => ('O', conn),
+                    elem::Type::PushedButton(c) => ('v', c.to_char()),
+                    elem::Type::ArmedButton(c) => ('^', c.to_char()),
+                    elem::Type::OpenBridge(c) => ('~', c.to_char()),
+                    elem::Type::ClosedBridge(c) => ('#', c.to_char()),
+                    elem::Type::Cube(c) => (c.to_char(), conn),
+                    elem::Type::House(c) | elem::Type::FullHouse(c) => (c.to_char().to_ascii_uppercase(), conn),
+                };
+                s.push(c);
+                s.push(extra);
+                if x + 1 < self.width {
+                    s.push(conn);
+                }
+            }
+            s.push('\n');
+        }
+        s
+    }
+
+    pub fn move_car(&mut self, car: &mut elem::Car, dir: Option<elem::Dir>) -> Result<(), MoveError> {
+        self.move_car_events(0, car, dir, &mut ())
     }
 
-    pub fn move_car(&mut self, car: &mut elem::Car, dir: Option<elem::Dir>) -> bool {
+    /// Move `car`, the `i`-th one, reporting to `events` what happens. Other
+    /// cars are not looked at: `game::Snapshot::next` moves them together.
+    pub fn move_car_events<S: event::Sink>(&mut self, i: usize, car: &mut elem::Car, dir: Option<elem::Dir>, events: &mut S) -> Result<(), MoveError> {
+        if let Some(color) = self.drive(i, car, dir, events)? {
+            self.press(color, events);
+        }
+        Ok(())
+    }
+
+    /// `move_car_events`, except that an armed button the car runs over is
+    /// only reported, its color returned for the caller to `press` it.
+    pub fn drive<S: event::Sink>(&mut self, i: usize, car: &mut elem::Car, dir: Option<elem::Dir>, events: &mut S) -> Result<Option<elem::Color>, MoveError> {
         if dir.is_none() {
-            return true;
+            return Ok(None);
         }
         let dir = dir.unwrap();
         // Check there is a road to move to.
         if !self[car.coord].connected(dir) {
-            return false;
+            return Err(MoveError::NoRoad);
+        }
+        if self.neighbour(car.coord, dir).is_none() {
+            return Err(MoveError::OffMap);
         }
 
         // Disconnect the road, move the car.
         self[car.coord].disconnect(dir);
-        self[car.coord].occupied = false;
+        let from = car.coord;
         car.roll(dir);
-        if self[car.coord].occupied {
-            return false;
-        }
         self[car.coord].disconnect(dir.rev());
-        self[car.coord].occupied = true;
-
+        events.push(event::Event::Moved { car: i, from, to: car.coord, dir });
 
         // Update state with game dynamics.
-        match self[car.coord].typ {
-            elem::Type::Empty => return false, // should not happen
-            elem::Type::Road => return true,
-            elem::Type::DropOff => return true,
+        let coord = car.coord;
+        match self[coord].typ {
+            elem::Type::Empty => Err(MoveError::NoRoad), // should not happen
+            elem::Type::Road => Ok(None),
+            elem::Type::DropOff => Ok(None),
             elem::Type::DropOn => {
-                self[car.coord].typ = elem::Type::DropOff;
-                if let Some(&e @ elem::Type::Cube(..)) = car.cubes.last() {
+                self[coord].typ = elem::Type::DropOff;
+                if let Some(&e @ elem::Type::Cube(c)) = car.cubes.last() {
                     // Drop the box on the ground
-                    self[car.coord].typ = e;
+                    self[coord].typ = e;
                     car.cubes.pop();
+                    events.push(event::Event::Dropped { car: i, cube: c, coord });
                 }
-                return true;
+                Ok(None)
             },
-            elem::Type::PushedButton(_) => return true,
+            elem::Type::PushedButton(_) => Ok(None),
             elem::Type::ArmedButton(c) => {
-                for x in self.iter_mut() {
-                    x.typ = match x.typ {
-                        elem::Type::ArmedButton(cc) if c == cc  =>  elem::Type::PushedButton(c),
-                        elem::Type::PushedButton(cc) if c == cc => elem::Type::ArmedButton(c),
-                        elem::Type::OpenBridge(cc) if c == cc   => elem::Type::ClosedBridge(c),
-                        elem::Type::ClosedBridge(cc) if c == cc => elem::Type::OpenBridge(c),
-                        _ => x.typ,
-                    }
-                }
-                return true;
+                events.push(event::Event::ButtonPressed { car: i, color: c, coord });
+                Ok(Some(c))
             }
-            elem::Type::OpenBridge(_) => return false,
-            elem::Type::ClosedBridge(_) => return true,
-            elem::Type::FullHouse(_) => return false,
-            elem::Type::House(_) if car.cubes.is_empty() => return false,
+            elem::Type::OpenBridge(_) => Err(MoveError::OpenBridge),
+            elem::Type::ClosedBridge(_) => Ok(None),
+            elem::Type::FullHouse(_) => Err(MoveError::FullHouse),
+            elem::Type::House(_) if car.cubes.is_empty() => Err(MoveError::EmptyTruck),
             elem::Type::House(c) => {
                 if let Some(&elem::Type::Cube(cc)) = car.cubes.last() {
                     if cc != c {
-                        return false;
+                        return Err(MoveError::WrongColor);
                     } else {
                         // Yield the cube to the house.
-                        self[car.coord].typ = elem::Type::FullHouse(c);
+                        self[coord].typ = elem::Type::FullHouse(c);
                         car.cubes.pop();
-                        return true;
+                        events.push(event::Event::Delivered { car: i, house: c, coord });
+                        return Ok(None);
                     }
                 }
-                return false;
+                Err(MoveError::WrongColor)
             },
-            elem::Type::Cube(..) if car.cubes.len() < 3 => {
+            elem::Type::Cube(c) if car.cubes.len() < 3 => {
                 // Steal the cube
-                car.cubes.push(self[car.coord].typ);
-                self[car.coord].typ = elem::Type::Road;
-                return true;
+                car.cubes.push(self[coord].typ);
+                self[coord].typ = elem::Type::Road;
+                events.push(event::Event::PickedUp { car: i, cube: c, coord });
+                Ok(None)
+            }
+            elem::Type::Cube(_) => Err(MoveError::Overloaded),
+        }
+    }
+
+    /// Switch every button and bridge of `color`.
+    pub fn press<S: event::Sink>(&mut self, color: elem::Color, events: &mut S) {
+        let width = self.width;
+        for (k, x) in self.iter_mut().enumerate() {
+            x.typ = match x.typ {
+                elem::Type::ArmedButton(c) if c == color  => elem::Type::PushedButton(c),
+                elem::Type::PushedButton(c) if c == color => elem::Type::ArmedButton(c),
+                elem::Type::OpenBridge(c) if c == color   => {
+                    events.push(event::Event::BridgeClosed { color, coord: (k % width, k / width) });
+                    elem::Type::ClosedBridge(c)
+                },
+                elem::Type::ClosedBridge(c) if c == color => {
+                    events.push(event::Event::BridgeOpened { color, coord: (k % width, k / width) });
+                    elem::Type::OpenBridge(c)
+                },
+                _ => x.typ,
             }
-            elem::Type::Cube(_) => return false,
         }
     }
 
-    pub fn check(&self, cars: &Vec<elem::Car>) -> bool {
-        for car in cars {
+    /// Return the index of the first car in a forbidden position.
+    pub fn check(&self, cars: &[elem::Car]) -> Result<(), (usize, MoveError)> {
+        for (i, car) in cars.iter().enumerate() {
             // Check wether a bridge opened AFTER a car moved.
             if let elem::Type::OpenBridge(_) = self[car.coord].typ {
-                return false;
+                return Err((i, MoveError::Stranded));
             }
         }
-        return true;
+        Ok(())
     }
 
-    pub fn output_solution(&mut self, solution: &solver::Solution, cars: &Vec<elem::Car>) {
-        let mut cars = cars.clone();
+    /// Turn on the drop zones selected by `targets`, in reading order, and
+    /// turn off the others.
+    pub fn set_targets(&mut self, targets: &[bool]) {
+        self.iter_mut().filter(|e| matches!(e.typ, elem::Type::DropOn | elem::Type::DropOff))
+                       .zip(targets)
+                       .map(|(e, &is_on)| e.typ = if is_on { elem::Type::DropOn } else { elem::Type::DropOff })
+                       .count();
+    }
 
-        self.iter_mut().filter(|e| {
-            match e.typ {
-                elem::Type::DropOn | elem::Type::DropOff => true,
-                _ => false,
-            }
-        }).zip(&solution.targets).map(|(e, &is_on)| { if is_on { e.typ = elem::Type::DropOn; } e }).count();
+    pub fn output_solution(&mut self, solution: &solver::Solution, cars: &[elem::Car], r: &dyn render::Renderer) {
+        self.set_targets(&solution.targets);
+        println!("{}", self.render_solution(&solution.dirs, cars, &[], r));
+    }
 
-        let mut rights: HashMap<(usize, usize), elem::Color> = HashMap::new();
-        let mut downs: HashMap<(usize, usize), elem::Color> = HashMap::new();
-        for (i, car) in cars.iter_mut().enumerate() {
-            for dir in solution.dirs.iter().map(|moves| moves[i]) {
+    /// Render the map with the roads travelled by `cars` following `dirs`
+    /// drawn in their colour, and `trucks` on top of the cells they stand on.
+    pub fn render_solution(&self, dirs: &[Vec<Option<elem::Dir>>], cars: &[elem::Car], trucks: &[elem::Car], r: &dyn render::Renderer) -> String {
+        let mut rights: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut downs: HashMap<(usize, usize), usize> = HashMap::new();
+        for (i, car) in cars.iter().enumerate() {
+            let mut car = car.clone();
+            for dir in dirs.iter().map(|moves| moves[i]) {
                 if dir.is_none() {
                     continue;
                 }
@@ -160,65 +343,92 @@ impl Map {
                 let old_coord = car.coord;
                 car.roll(dir);
                 match dir {
-                    elem::Dir::Up => downs.insert(car.coord, car.color),
-                    elem::Dir::Right => rights.insert(old_coord, car.color),
-                    elem::Dir::Down => downs.insert(old_coord, car.color),
-                    elem::Dir::Left => rights.insert(car.coord, car.color),
+                    elem::Dir::Up => downs.insert(car.coord, i),
+                    elem::Dir::Right => rights.insert(old_coord, i),
+                    elem::Dir::Down => downs.insert(old_coord, i),
+                    elem::Dir::Left => rights.insert(car.coord, i),
                 };
             }
         }
+        self.render_paths(r, cars, &rights, &downs, trucks)
+    }
 
-        for (r, row) in self.undl.chunks(self.width).enumerate() {
-            for (c, col) in row.iter().enumerate() {
-                let mut conn = "  ".to_string();
-                if col.connected(elem::Dir::Right) {
-                    if let Some(color) = rights.get(&(c, r)) {
-                        conn = color.colorize("--");
-                    } else {
-                        conn = "--".to_string();
-                    }
+    /// The cell next to `coord` in direction `dir`, if it is on the map.
+    pub fn neighbour(&self, (x, y): (usize, usize), dir: elem::Dir) -> Option<(usize, usize)> {
+        let (x, y) = match dir {
+            elem::Dir::Left  => (x.checked_sub(1)?, y),
+            elem::Dir::Up    => (x, y.checked_sub(1)?),
+            elem::Dir::Right => (x + 1, y),
+            elem::Dir::Down  => (x, y + 1),
+        };
+        if x < self.width && y < self.height { Some((x, y)) } else { None }
+    }
+
+    /// True once every house got its cube.
+    pub fn is_solved(&self) -> bool {
+        !self.iter().any(|item| matches!(item.typ, elem::Type::House(_)))
+    }
+
+    pub fn render(&self, r: &dyn render::Renderer) -> String {
+        self.render_paths(r, &[], &HashMap::new(), &HashMap::new(), &[])
+    }
+
+    /// Render the map with `cars` drawn at their position.
+    pub fn render_cars(&self, r: &dyn render::Renderer, cars: &[elem::Car]) -> String {
+        self.render_paths(r, &[], &HashMap::new(), &HashMap::new(), cars)
+    }
+
+    /// Render the map, drawing the connectors found in `rights` and `downs`
+    /// as travelled by the car of the associated index, and `trucks` on top
+    /// of the cells they stand on.
+    fn render_paths(&self,
+                    r: &dyn render::Renderer,
+                    cars: &[elem::Car],
+                    rights: &HashMap<(usize, usize), usize>,
+                    downs: &HashMap<(usize, usize), usize>,
+                    trucks: &[elem::Car]) -> String {
+        let mut s = String::new();
+        for (y, row) in self.undl.chunks(self.width).enumerate() {
+            if y > 0 {
+                s.push('\n');
+            }
+            for (x, col) in row.iter().enumerate() {
+                let mut conn = if col.connected(elem::Dir::Right) { "--" } else { "  " };
+                let tag = match trucks.iter().position(|car| car.coord == (x, y)) {
+                    Some(i) => {
+                        s.push_str(&r.truck(i, &trucks[i]));
+                        r.truck_tag(i, &trucks[i])
+                    },
+                    None => {
+                        s.push_str(&r.elem(col));
+                        r.tag(col)
+                    },
+                };
+                if let Some(tag) = tag {
+                    s.push(tag);
+                    conn = &conn[1..];
+                }
+                match rights.get(&(x, y)) {
+                    Some(&i) if col.connected(elem::Dir::Right) => s.push_str(&r.path(conn, i, &cars[i])),
+                    _ => s.push_str(conn),
                 }
-                print!("{}{}", col, conn);
             }
-            println!("");
-            for (c, col) in row.iter().enumerate() {
-                let mut conn = " ".to_string();
-                if col.connected(elem::Dir::Down) {
-                    if let Some(color) = downs.get(&(c, r)) {
-                        conn = color.colorize("|");
-                    } else {
-                        conn = "|".to_string();
-                    }
+            s.push('\n');
+            for (x, col) in row.iter().enumerate() {
+                match downs.get(&(x, y)) {
+                    _ if !col.connected(elem::Dir::Down) => s.push(' '),
+                    Some(&i) => s.push_str(&r.path("|", i, &cars[i])),
+                    None => s.push('|'),
                 }
-                print!("{}  ", conn);
+                s.push_str("  ");
             }
-            println!("");
         }
+        s
     }
 }
 
 impl fmt::Display for Map {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
-        for (r, row) in self.undl.chunks(self.width).enumerate() {
-            if r > 0 {
-                try!(write!(fmt, "\n"));
-            }
-            for x in row {
-                let mut conn = "  ";
-                if x.connected(elem::Dir::Right) {
-                    conn = "--";
-                }
-                try!(write!(fmt, "{}{}", x, conn));
-            }
-            try!(write!(fmt, "\n"));
-            for x in row {
-                let mut conn = " ";
-                if x.connected(elem::Dir::Down) {
-                    conn = "|";
-                }
-                try!(write!(fmt, "{}  ", conn));
-            }
-        }
-        result::Result::Ok(())
+        write!(fmt, "{}", self.render(&render::Ansi))
     }
 }
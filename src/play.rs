@@ -0,0 +1,241 @@
+use std::io::{self, Read, Write};
+use ::map;
+use ::elem;
+use ::solver;
+use ::render;
+use ::route;
+use ::hint;
+use ::term;
+use ::game;
+
+/// States the solver may explore for a hint, to keep the player responsive.
+const HINT_BUDGET: usize = 1_000_000;
+
+enum Action {
+    Move(elem::Dir),
+    Next,
+    Select(usize),
+    Target,
+    Undo,
+    Hint,
+    Done,
+    Quit,
+}
+
+enum Edit {
+    Step(usize),
+    Target(usize),
+}
+
+/// Why the routes drawn so far cannot be played.
+#[derive(Debug)]
+struct Failure {
+    tick: usize,
+    car: usize,
+    error: map::MoveError,
+}
+
+/// A level being played: the route drawn for every car and the drop zones
+/// chosen as targets. Routes are always kept legal.
+struct Session {
+    map: map::Map,
+    cars: Vec<elem::Car>,
+    routes: Vec<Vec<elem::Dir>>,
+    targets: Vec<bool>,
+    history: Vec<Edit>,
+    current: usize,
+}
+
+impl Session {
+    fn solution(&self) -> solver::Solution {
+        let ticks = self.routes.iter().map(|route| route.len()).max().unwrap_or(0);
+        solver::Solution {
+            dirs: (0..ticks).map(|t| self.routes.iter().map(|route| route.get(t).cloned()).collect()).collect(),
+            targets: self.targets.clone(),
+        }
+    }
+
+    /// Play the routes and return the game at their end.
+    fn simulate(&self) -> Result<game::Game, Failure> {
+        let mut game = game::Game::new(self.map.clone(), self.cars.clone());
+        game.set_targets(&self.targets);
+        for (tick, moves) in self.solution().dirs.iter().enumerate() {
+            if let Err((car, error)) = game.step_events(moves, &mut ()) {
+                return Err(Failure { tick: tick + 1, car, error });
+            }
+        }
+        Ok(game)
+    }
+
+    /// Apply `edit`, reverting it if it makes the routes illegal.
+    fn apply(&mut self, edit: Edit) -> Result<(), Failure> {
+        match edit {
+            Edit::Step(_) => {},
+            Edit::Target(i) => self.targets[i] = !self.targets[i],
+        }
+        match self.simulate() {
+            Ok(_) => {
+                self.history.push(edit);
+                Ok(())
+            },
+            Err(failure) => {
+                self.revert(edit);
+                Err(failure)
+            },
+        }
+    }
+
+    fn revert(&mut self, edit: Edit) {
+        match edit {
+            Edit::Step(i) => { self.routes[i].pop(); },
+            Edit::Target(i) => self.targets[i] = !self.targets[i],
+        }
+    }
+
+    fn undo(&mut self) {
+        if let Some(edit) = self.history.pop() {
+            self.revert(edit);
+        }
+    }
+
+    fn extend(&mut self, dir: elem::Dir) -> Result<(), Failure> {
+        self.routes[self.current].push(dir);
+        self.apply(Edit::Step(self.current))
+    }
+
+    /// Toggle the drop zone the current car stands on.
+    fn toggle_target(&mut self) -> Result<(), String> {
+        let game = self.simulate().expect("routes are kept legal");
+        let coord = game.cars()[self.current].coord;
+        let width = game.map().width;
+        let zone = self.map.iter().enumerate()
+                                  .filter(|&(_, e)| matches!(e.typ, elem::Type::DropOn | elem::Type::DropOff))
+                                  .position(|(i, _)| (i % width, i / width) == coord);
+        match zone {
+            Some(zone) => self.apply(Edit::Target(zone)).map_err(|f| describe(&f)),
+            None => Err("the truck is not on a drop zone".to_string()),
+        }
+    }
+
+    fn draw(&self, r: &dyn render::Renderer, message: &str) {
+        let game = self.simulate().expect("routes are kept legal");
+        let (mut map, cars) = (game.map().clone(), game.cars());
+        // Keep showing the roads that have been used, painted.
+        for (e, orig) in map.iter_mut().zip(self.map.iter()) {
+            e.conn = orig.conn;
+        }
+        let mut out = String::from(term::clear());
+        out.push_str("arrows/hjkl: drive, tab/0-9: select truck, o: toggle drop zone, u: undo, ?: hint, enter: done, q: quit\n\n");
+        out.push_str(&map.render_solution(&self.solution().dirs, &self.cars, cars, r));
+        out.push('\n');
+        for (i, car) in cars.iter().enumerate() {
+            let route = r.route(&route::Route(self.routes[i].iter().map(|&dir| Some(dir)).collect()));
+            out.push_str(&format!("{} {} ({}, {}): {} [",
+                                  if i == self.current { ">" } else { " " },
+                                  r.car(i, &self.cars[i]), car.coord.0, car.coord.1, route));
+            for &cube in car.cubes.iter() {
+                let e = elem::Elem { typ: cube, ..Default::default() };
+                out.push_str(&r.elem(&e));
+                if let Some(tag) = r.tag(&e) {
+                    out.push(tag);
+                }
+            }
+            out.push_str("]\n");
+        }
+        out.push('\n');
+        out.push_str(message);
+        out.push('\n');
+        print!("{}", out);
+        io::stdout().flush().unwrap();
+    }
+}
+
+fn describe(failure: &Failure) -> String {
+    format!("ILLEGAL: truck {} at tick {}: {}", failure.car, failure.tick, failure.error)
+}
+
+fn action(key: term::Key) -> Option<Action> {
+    Some(match key {
+        term::Key::Up | term::Key::Char('k') => Action::Move(elem::Dir::Up),
+        term::Key::Down | term::Key::Char('j') => Action::Move(elem::Dir::Down),
+        term::Key::Right | term::Key::Char('l') => Action::Move(elem::Dir::Right),
+        term::Key::Left | term::Key::Char('h') => Action::Move(elem::Dir::Left),
+        term::Key::Tab => Action::Next,
+        term::Key::Char(c @ '0'..='9') => Action::Select(c as usize - '0' as usize),
+        term::Key::Char('o') => Action::Target,
+        term::Key::Char('u') | term::Key::Backspace => Action::Undo,
+        term::Key::Char('?') => Action::Hint,
+        term::Key::Enter => Action::Done,
+        term::Key::Char('q') => Action::Quit,
+        _ => return None,
+    })
+}
+
+/// Let the user draw the route of every car, then play them. Return true if
+/// the level is solved.
+pub fn play(map: &map::Map, cars: &[elem::Car], r: &dyn render::Renderer) -> bool {
+    if cars.is_empty() {
+        println!("FAILURE: the level has no truck to drive");
+        return false;
+    }
+    let zones = map.iter().filter(|e| matches!(e.typ, elem::Type::DropOn | elem::Type::DropOff)).count();
+    let mut session = Session {
+        map: map.clone(),
+        cars: cars.to_vec(),
+        routes: vec![vec![]; cars.len()],
+        targets: vec![false; zones],
+        history: vec![],
+        current: 0,
+    };
+
+    let raw = term::RawMode::new();
+    let stdin = io::stdin();
+    let mut input = stdin.lock().bytes().map_while(Result::ok);
+    let mut message = String::new();
+    loop {
+        session.draw(r, &message);
+        message.clear();
+        let key = loop {
+            match term::read_key(&mut input) {
+                Some(key) => if let Some(key) = action(key) {
+                    break Some(key);
+                },
+                None => break None,
+            }
+        };
+        match key {
+            Some(Action::Move(dir)) => if let Err(failure) = session.extend(dir) {
+                message = describe(&failure);
+            },
+            Some(Action::Next) => session.current = (session.current + 1).checked_rem(cars.len()).unwrap_or(0),
+            Some(Action::Select(i)) if i < cars.len() => session.current = i,
+            Some(Action::Select(_)) => message = "no such truck".to_string(),
+            Some(Action::Target) => if let Err(e) = session.toggle_target() {
+                message = e;
+            },
+            Some(Action::Undo) => session.undo(),
+            Some(Action::Hint) => message = match hint::hint(&session.map, &session.cars, &session.routes, Some(&session.targets), 1, Some(HINT_BUDGET)) {
+                hint::Hint::Moves(moves) => format!("HINT: {}", moves.iter().enumerate().map(|(i, m)| {
+                    format!("truck {}: {}", i, m.first().map_or("stop".to_string(), |&d| r.dir(d)))
+                }).collect::<Vec<_>>().join(", ")),
+                hint::Hint::Unsolvable { tick: 0 } => "HINT: no solution with these drop zones".to_string(),
+                hint::Hint::Unsolvable { tick } => format!("HINT: no solution since tick {}, undo some moves", tick),
+                hint::Hint::GaveUp => "HINT: too hard to tell, keep going".to_string(),
+            },
+            Some(Action::Done) => break,
+            Some(Action::Quit) | None => return false,
+        }
+    }
+    drop(raw);
+
+    // Routes are kept legal, so the only way to fail is to leave houses empty.
+    let end = session.simulate().expect("routes are kept legal");
+    if end.is_won() {
+        println!("SUCCESS: every house got its cube in {} ticks", session.solution().dirs.len());
+        true
+    } else {
+        let left = end.map().iter().filter(|e| matches!(e.typ, elem::Type::House(_))).count();
+        println!("FAILURE: {} house(s) still waiting for a cube", left);
+        false
+    }
+}
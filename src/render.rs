@@ -0,0 +1,228 @@
+use std::env;
+use std::io::{self, IsTerminal};
+use ::elem;
+use ::route;
+
+/// How map cells, cars and travelled roads are drawn on a terminal.
+///
+/// Every cell is followed by a two column wide horizontal connector. A
+/// renderer may claim the first column of that connector with `tag` to
+/// print extra information about the cell, like the map syntax does for
+/// button and bridge colours.
+pub trait Renderer {
+    fn elem(&self, e: &elem::Elem) -> String;
+    fn tag(&self, e: &elem::Elem) -> Option<char>;
+    /// Draw the `index`-th car of the level.
+    fn car(&self, index: usize, car: &elem::Car) -> String;
+    /// Draw the `index`-th car inside the cell it stands on.
+    fn truck(&self, index: usize, car: &elem::Car) -> String;
+    fn truck_tag(&self, index: usize, car: &elem::Car) -> Option<char>;
+    fn dir(&self, dir: elem::Dir) -> String;
+    /// Write the moves of a car in `route::Route` notation.
+    fn route(&self, route: &route::Route) -> String;
+    /// Draw the road segment `s` as travelled by the `index`-th car.
+    fn path(&self, s: &str, index: usize, car: &elem::Car) -> String;
+}
+
+/// 256-colour ANSI escapes and emoji.
+pub struct Ansi;
+
+impl Renderer for Ansi {
+    fn elem(&self, e: &elem::Elem) -> String {
+        e.to_string()
+    }
+
+    fn tag(&self, _: &elem::Elem) -> Option<char> {
+        None
+    }
+
+    fn car(&self, _: usize, car: &elem::Car) -> String {
+        car.to_string()
+    }
+
+    fn truck(&self, _: usize, car: &elem::Car) -> String {
+        car.to_string()
+    }
+
+    fn truck_tag(&self, _: usize, _: &elem::Car) -> Option<char> {
+        None
+    }
+
+    fn dir(&self, dir: elem::Dir) -> String {
+        dir.to_string()
+    }
+
+    fn route(&self, route: &route::Route) -> String {
+        format!("{:#}", route)
+    }
+
+    fn path(&self, s: &str, _: usize, car: &elem::Car) -> String {
+        car.color.colorize(s)
+    }
+}
+
+/// Pure 7-bit ASCII, using the map syntax: lowercase letters for cubes,
+/// uppercase for houses and the colour letter as tag for the rest.
+/// Travelled roads are drawn with the colour letter of the car.
+pub struct Ascii;
+
+impl Renderer for Ascii {
+    fn elem(&self, e: &elem::Elem) -> String {
+        match e.typ {
+            elem::Type::Empty           => ' ',
+            elem::Type::Road            => 'x',
+            elem::Type::DropOn          => '@',
+            elem::Type::DropOff         => 'O',
+            elem::Type::PushedButton(_) => 'v',
+            elem::Type::ArmedButton(_)  => '^',
+            elem::Type::OpenBridge(_)   => '~',
+            elem::Type::ClosedBridge(_) => '#',
+            elem::Type::Cube(c)         => c.to_char(),
+            elem::Type::House(c)        => c.to_char().to_ascii_uppercase(),
+            elem::Type::FullHouse(c)    => c.to_char().to_ascii_uppercase(),
+        }.to_string()
+    }
+
+    fn tag(&self, e: &elem::Elem) -> Option<char> {
+        match e.typ {
+            elem::Type::PushedButton(c) |
+            elem::Type::ArmedButton(c) |
+            elem::Type::OpenBridge(c) |
+            elem::Type::ClosedBridge(c) => Some(c.to_char()),
+            elem::Type::FullHouse(_) => Some('*'),
+            _ => None,
+        }
+    }
+
+    fn car(&self, _: usize, car: &elem::Car) -> String {
+        format!("T{}", car.color.to_char())
+    }
+
+    fn truck(&self, _: usize, _: &elem::Car) -> String {
+        "T".to_string()
+    }
+
+    fn truck_tag(&self, _: usize, car: &elem::Car) -> Option<char> {
+        Some(car.color.to_char())
+    }
+
+    fn dir(&self, dir: elem::Dir) -> String {
+        match dir {
+            elem::Dir::Up    => "U",
+            elem::Dir::Right => "R",
+            elem::Dir::Down  => "D",
+            elem::Dir::Left  => "L",
+        }.to_string()
+    }
+
+    fn route(&self, route: &route::Route) -> String {
+        route.to_string()
+    }
+
+    fn path(&self, s: &str, _: usize, car: &elem::Car) -> String {
+        s.chars().map(|_| car.color.to_char()).collect()
+    }
+}
+
+/// ANSI for colour-blind readers: a palette that stays apart under the
+/// common colour vision deficiencies, every coloured cell tagged with its
+/// colour letter and travelled roads drawn with the index of the car.
+pub struct Accessible;
+
+impl Accessible {
+    fn paint(color: elem::Color, s: &str) -> String {
+        format!("{}{}\x1b[0m", match color {
+            elem::Color::Red    => "\x1b[38;5;166m",
+            elem::Color::Green  => "\x1b[38;5;36m",
+            elem::Color::Blue   => "\x1b[38;5;25m",
+            elem::Color::Yellow => "\x1b[38;5;227m",
+            elem::Color::White  => "\x1b[38;5;255m",
+            elem::Color::Orange => "\x1b[38;5;214m",
+            elem::Color::Violet => "\x1b[38;5;97m",
+            elem::Color::Pink   => "\x1b[38;5;175m",
+            elem::Color::Cream  => "\x1b[38;5;230m",
+        }, s)
+    }
+}
+
+impl Renderer for Accessible {
+    fn elem(&self, e: &elem::Elem) -> String {
+        match e.typ {
+            elem::Type::Empty           => " ".to_string(),
+            elem::Type::Road            => "x".to_string(),
+            elem::Type::DropOn          => "🞋".to_string(),
+            elem::Type::DropOff         => "🞋".to_string(),
+            elem::Type::PushedButton(c) => Accessible::paint(c, "🔳"),
+            elem::Type::ArmedButton(c)  => Accessible::paint(c, "🔲"),
+            elem::Type::OpenBridge(c)   => Accessible::paint(c, "≋"),
+            elem::Type::ClosedBridge(c) => Accessible::paint(c, "⌒"),
+            elem::Type::Cube(c)         => Accessible::paint(c, "📦"),
+            elem::Type::House(c)        => Accessible::paint(c, "🏫"),
+            elem::Type::FullHouse(c)    => Accessible::paint(c, "🏫"),
+        }
+    }
+
+    fn tag(&self, e: &elem::Elem) -> Option<char> {
+        match e.typ {
+            elem::Type::DropOn => Some('*'),
+            elem::Type::FullHouse(c) => Some(c.to_char().to_ascii_uppercase()),
+            elem::Type::PushedButton(c) |
+            elem::Type::ArmedButton(c) |
+            elem::Type::OpenBridge(c) |
+            elem::Type::ClosedBridge(c) |
+            elem::Type::Cube(c) |
+            elem::Type::House(c) => Some(c.to_char()),
+            _ => None,
+        }
+    }
+
+    fn car(&self, index: usize, car: &elem::Car) -> String {
+        format!("{}{}{}", index_char(index), Accessible::paint(car.color, "🚚"), car.color.to_char())
+    }
+
+    fn truck(&self, _: usize, car: &elem::Car) -> String {
+        Accessible::paint(car.color, "🚚")
+    }
+
+    fn truck_tag(&self, index: usize, _: &elem::Car) -> Option<char> {
+        Some(index_char(index))
+    }
+
+    fn dir(&self, dir: elem::Dir) -> String {
+        dir.to_string()
+    }
+
+    fn route(&self, route: &route::Route) -> String {
+        format!("{:#}", route)
+    }
+
+    fn path(&self, s: &str, index: usize, car: &elem::Car) -> String {
+        let label: String = s.chars().map(|_| index_char(index)).collect();
+        Accessible::paint(car.color, &label)
+    }
+}
+
+/// Single character label for a car index: `0`-`9` then `a`-`z`.
+fn index_char(index: usize) -> char {
+    std::char::from_digit(index as u32 % 36, 36).unwrap()
+}
+
+/// ASCII when `NO_COLOR` is set or stdout is not a terminal, ANSI otherwise.
+pub fn auto() -> Box<dyn Renderer> {
+    let no_color = env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    if no_color || !io::stdout().is_terminal() {
+        Box::new(Ascii)
+    } else {
+        Box::new(Ansi)
+    }
+}
+
+pub fn from_name(name: &str) -> Option<Box<dyn Renderer>> {
+    match name {
+        "auto"  => Some(auto()),
+        "ansi"  => Some(Box::new(Ansi)),
+        "ascii" => Some(Box::new(Ascii)),
+        "accessible" => Some(Box::new(Accessible)),
+        _       => None,
+    }
+}
@@ -0,0 +1,80 @@
+use std::io::{self, Read, Write};
+use std::thread;
+use std::time;
+use ::map;
+use ::elem;
+use ::solver;
+use ::render;
+use ::term;
+
+/// How fast a replay moves from one tick to the next.
+pub enum Pace {
+    /// Wait for the given duration between ticks.
+    Delay(time::Duration),
+    /// Wait for the user: space, enter or right moves forward, `b` or left
+    /// back, `q` quits.
+    Step,
+}
+
+/// Play `solution` in the terminal, redrawing the map at every tick.
+pub fn replay(map: &map::Map, cars: &[elem::Car], solution: &solver::Solution, r: &dyn render::Renderer, pace: Pace) {
+    // Simulate everything first so that it is possible to step back.
+    let frames = solver::simulate(map, cars, solution);
+    if frames.len() <= solution.dirs.len() {
+        eprintln!("illegal moves at tick {}", frames.len());
+    }
+
+    let last = frames.len() - 1;
+    let raw = match pace {
+        Pace::Step => Some(term::RawMode::new()),
+        Pace::Delay(_) => None,
+    };
+    let stdin = io::stdin();
+    let mut input = stdin.lock().bytes().map_while(Result::ok);
+    let mut tick = 0;
+    loop {
+        let (ref map, ref cars) = frames[tick];
+        draw(map, cars, tick, last, r);
+        match pace {
+            Pace::Delay(delay) => {
+                if tick == last {
+                    break;
+                }
+                thread::sleep(delay);
+                tick += 1;
+            },
+            Pace::Step => {
+                println!("[space] next, [b] back, [q] quit");
+                match term::read_key(&mut input) {
+                    Some(term::Key::Char('b')) | Some(term::Key::Left) | Some(term::Key::Backspace) => tick = tick.saturating_sub(1),
+                    Some(term::Key::Char('q')) | None => break,
+                    Some(term::Key::Char(' ')) | Some(term::Key::Enter) | Some(term::Key::Right) if tick < last => tick += 1,
+                    Some(term::Key::Char(' ')) | Some(term::Key::Enter) | Some(term::Key::Right) => break,
+                    Some(_) => {},
+                }
+            },
+        }
+    }
+    drop(raw);
+}
+
+fn draw(map: &map::Map, cars: &[elem::Car], tick: usize, last: usize, r: &dyn render::Renderer) {
+    let mut out = String::from(term::clear());
+    out.push_str(&format!("TICK {}/{}\n", tick, last));
+    out.push_str(&map.render_cars(r, cars));
+    out.push('\n');
+    for (i, car) in cars.iter().enumerate() {
+        out.push_str(&format!("{} ({}, {}):", r.car(i, car), car.coord.0, car.coord.1));
+        for &cube in car.cubes.iter() {
+            let e = elem::Elem { typ: cube, ..Default::default() };
+            out.push(' ');
+            out.push_str(&r.elem(&e));
+            if let Some(tag) = r.tag(&e) {
+                out.push(tag);
+            }
+        }
+        out.push('\n');
+    }
+    print!("{}", out);
+    io::stdout().flush().unwrap();
+}
@@ -0,0 +1,194 @@
+use std::fmt::Write;
+use ::map;
+use ::elem;
+use ::event;
+use ::solver;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum CargoKind {
+    PickUp,
+    Delivery,
+    Drop,
+}
+
+impl CargoKind {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            CargoKind::PickUp   => "pickup",
+            CargoKind::Delivery => "delivery",
+            CargoKind::Drop     => "drop",
+        }
+    }
+}
+
+/// A cube changing hands at a given tick.
+#[derive(Copy, Clone, Debug)]
+pub struct Cargo {
+    pub tick: usize,
+    pub coord: (usize, usize),
+    pub kind: CargoKind,
+    pub color: elem::Color,
+}
+
+pub struct CarReport {
+    pub color: elem::Color,
+    pub start: (usize, usize),
+    pub moves: Vec<Option<elem::Dir>>,
+    /// Position after each tick, `positions[0]` being the start.
+    pub positions: Vec<(usize, usize)>,
+    pub cargo: Vec<Cargo>,
+}
+
+pub struct Report {
+    /// Coordinates of the drop zones chosen as targets.
+    pub targets: Vec<(usize, usize)>,
+    pub cars: Vec<CarReport>,
+}
+
+impl Report {
+    /// Play `solution` on `map` and record everything that happens to each
+    /// car, up to the first illegal tick.
+    pub fn new(map: &map::Map, cars: &[elem::Car], solution: &solver::Solution) -> Report {
+        let mut targets_map = map.clone();
+        targets_map.set_targets(&solution.targets);
+        let width = map.width;
+        let targets = targets_map.iter().enumerate()
+                                 .filter(|&(_, e)| matches!(e.typ, elem::Type::DropOn))
+                                 .map(|(i, _)| (i % width, i / width))
+                                 .collect();
+
+        let timeline = solution.timeline(map, cars);
+        let mut reports: Vec<CarReport> = cars.iter().enumerate().map(|(i, car)| CarReport {
+            color: car.color,
+            start: car.coord,
+            moves: solution.dirs.iter().map(|moves| moves[i]).collect(),
+            positions: timeline.iter().map(|tick| tick.cars[i].coord).collect(),
+            cargo: vec![],
+        }).collect();
+
+        let ticks = solver::events(map, cars, solution);
+        for (tick, events) in ticks.iter().enumerate().take(timeline.len() - 1) {
+            for event in events.iter() {
+                let (car, kind, color, coord) = match *event {
+                    event::Event::PickedUp { car, cube, coord }   => (car, CargoKind::PickUp, cube, coord),
+                    event::Event::Delivered { car, house, coord } => (car, CargoKind::Delivery, house, coord),
+                    event::Event::Dropped { car, cube, coord }    => (car, CargoKind::Drop, cube, coord),
+                    _ => continue,
+                };
+                reports[car].cargo.push(Cargo {
+                    tick: tick + 1,
+                    coord,
+                    kind,
+                    color,
+                });
+            }
+        }
+
+        Report {
+            targets,
+            cars: reports,
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut s = String::new();
+        s.push_str("{\"targets\":[");
+        s.push_str(&self.targets.iter().map(|&c| coord_json(c)).collect::<Vec<_>>().join(","));
+        s.push_str("],\"cars\":[");
+        for (i, car) in self.cars.iter().enumerate() {
+            if i > 0 {
+                s.push(',');
+            }
+            write!(s, "{{\"color\":\"{}\",\"start\":{},", car.color.name(), coord_json(car.start)).unwrap();
+            s.push_str("\"moves\":[");
+            s.push_str(&car.moves.iter().map(|&d| dir_json(d)).collect::<Vec<_>>().join(","));
+            s.push_str("],\"runs\":[");
+            s.push_str(&group(&car.moves).into_iter()
+                                        .map(|(d, n)| format!("{{\"dir\":{},\"n\":{}}}", dir_json(d), n))
+                                        .collect::<Vec<_>>().join(","));
+            s.push_str("],\"positions\":[");
+            s.push_str(&car.positions.iter().map(|&c| coord_json(c)).collect::<Vec<_>>().join(","));
+            s.push_str("],\"cargo\":[");
+            s.push_str(&car.cargo.iter()
+                                 .map(|c| format!("{{\"tick\":{},\"kind\":\"{}\",\"color\":\"{}\",\"at\":{}}}",
+                                                  c.tick, c.kind.name(), c.color.name(), coord_json(c.coord)))
+                                 .collect::<Vec<_>>().join(","));
+            s.push_str("]}");
+        }
+        s.push_str("]}");
+        s
+    }
+
+    /// One row per car and tick. `label` fills the first column so that
+    /// several reports can be concatenated.
+    pub fn to_csv(&self, label: &str) -> String {
+        let mut s = String::new();
+        for (i, car) in self.cars.iter().enumerate() {
+            for (tick, &(x, y)) in car.positions.iter().enumerate() {
+                let dir = if tick == 0 { None } else { car.moves[tick - 1] };
+                let cargo = car.cargo.iter()
+                                     .filter(|c| c.tick == tick)
+                                     .map(|c| format!("{}:{}", c.kind.name(), c.color.name()))
+                                     .collect::<Vec<_>>()
+                                     .join(" ");
+                writeln!(s, "{},{},{},{},{},{},{},{}",
+                         label, i, car.color.name(), tick, x, y,
+                         dir.map(|d| d.name()).unwrap_or(""), cargo).unwrap();
+            }
+        }
+        s
+    }
+}
+
+pub const CSV_HEADER: &str = "scenario,car,color,tick,x,y,move,cargo";
+
+fn coord_json((x, y): (usize, usize)) -> String {
+    format!("[{},{}]", x, y)
+}
+
+fn dir_json(dir: Option<elem::Dir>) -> String {
+    match dir {
+        Some(d) => format!("\"{}\"", d.name()),
+        None    => "null".to_string(),
+    }
+}
+
+/// Run-length encode a sequence, e.g. `[a, a, b]` => `[(a, 2), (b, 1)]`.
+pub fn group<T: Eq + Copy>(v: &[T]) -> Vec<(T, usize)> {
+    let mut r = vec![];
+    if v.is_empty() {
+        return r;
+    }
+
+    let mut last = v.first().unwrap();
+    let mut n = 1;
+    for x in v.iter().skip(1) {
+        if *x != *last {
+            r.push((*last, n));
+            n = 0;
+            last = x;
+        }
+        n += 1;
+    }
+    r.push((*last, n));
+    r
+}
+
+/// Quote `s` as a JSON string, also safe to embed in an HTML script.
+pub fn json_string(s: &str) -> String {
+    let mut r = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"'  => r.push_str("\\\""),
+            '\\' => r.push_str("\\\\"),
+            '\n' => r.push_str("\\n"),
+            '\r' => r.push_str("\\r"),
+            '\t' => r.push_str("\\t"),
+            '<'  => r.push_str("\\u003c"),
+            _ if c < ' ' => r.push_str(&format!("\\u{:04x}", c as u32)),
+            _    => r.push(c),
+        }
+    }
+    r.push('"');
+    r
+}
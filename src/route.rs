@@ -0,0 +1,101 @@
+use std::fmt;
+use std::str;
+use ::elem;
+use ::report;
+use ::solver;
+
+/// Longest run of a route, far beyond what any level needs.
+pub const MAX_COUNT: usize = 10_000;
+
+/// The moves of one car, one per tick, `None` when it waits.
+///
+/// Written as runs of a count and a move, e.g. `2R 3D 1.`: `U`, `R`, `D`
+/// and `L` or `↑`, `→`, `↓` and `←` for the directions and `.` for waiting.
+/// Counts of one may be left out and spaces between runs too, so `RRDDD.`
+/// and `2→3↓.` read the same. `{}` writes letters and `{:#}` arrows.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Route(pub Vec<Option<elem::Dir>>);
+
+impl Route {
+    /// The moves of the car `i` in `solution`.
+    pub fn of(solution: &solver::Solution, i: usize) -> Route {
+        Route(solution.dirs.iter().map(|moves| moves[i]).collect())
+    }
+
+    /// The moves up to the first wait, or None if the car moves again
+    /// after waiting, which the rules forbid.
+    pub fn moves(&self) -> Option<Vec<elem::Dir>> {
+        let moves: Vec<elem::Dir> = self.0.iter().map_while(|&dir| dir).collect();
+        if self.0[moves.len()..].iter().any(|dir| dir.is_some()) {
+            return None;
+        }
+        Some(moves)
+    }
+
+    /// The route without the waits it ends with.
+    pub fn trimmed(&self) -> Route {
+        let len = self.0.iter().rposition(|dir| dir.is_some()).map_or(0, |i| i + 1);
+        Route(self.0[..len].to_vec())
+    }
+}
+
+/// A solution in which the car `i` follows `routes[i]` and waits once it
+/// ends.
+pub fn solution(routes: &[Route], targets: Vec<bool>) -> solver::Solution {
+    let ticks = routes.iter().map(|route| route.0.len()).max().unwrap_or(0);
+    solver::Solution {
+        dirs: (0..ticks).map(|t| routes.iter().map(|route| route.0.get(t).cloned().unwrap_or(None)).collect()).collect(),
+        targets,
+    }
+}
+
+impl str::FromStr for Route {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Route, String> {
+        let mut moves = vec![];
+        let mut count = None;
+        for (column, c) in s.chars().enumerate() {
+            let dir = match c {
+                'U' | '↑' => Some(elem::Dir::Up),
+                'R' | '→' => Some(elem::Dir::Right),
+                'D' | '↓' => Some(elem::Dir::Down),
+                'L' | '←' => Some(elem::Dir::Left),
+                '.' => None,
+                '0'..='9' => {
+                    let n = count.unwrap_or(0) * 10 + (c as usize - '0' as usize);
+                    if n > MAX_COUNT {
+                        return Err(format!("count too large at column {}", column + 1));
+                    }
+                    count = Some(n);
+                    continue;
+                },
+                _ if c.is_whitespace() && count.is_none() => continue,
+                _ if c.is_whitespace() => return Err(format!("count without a move at column {}", column + 1)),
+                _ => return Err(format!("unknown move `{}` at column {}", c, column + 1)),
+            };
+            match count.take().unwrap_or(1) {
+                0 => return Err(format!("zero count at column {}", column + 1)),
+                n => moves.extend((0..n).map(|_| dir)),
+            }
+        }
+        if count.is_some() {
+            return Err("count without a move at the end".to_string());
+        }
+        Ok(Route(moves))
+    }
+}
+
+impl fmt::Display for Route {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        let runs: Vec<String> = report::group(&self.0).into_iter().map(|(dir, n)| match dir {
+            Some(dir) if fmt.alternate() => format!("{}{}", n, dir),
+            Some(elem::Dir::Up)    => format!("{}U", n),
+            Some(elem::Dir::Right) => format!("{}R", n),
+            Some(elem::Dir::Down)  => format!("{}D", n),
+            Some(elem::Dir::Left)  => format!("{}L", n),
+            None                   => format!("{}.", n),
+        }).collect();
+        write!(fmt, "{}", runs.join(" "))
+    }
+}
@@ -0,0 +1,331 @@
+use ::elem;
+use ::level;
+
+/// A built-in level: a map in the text syntax and the cars starting on it.
+pub struct Scenario {
+    pub map: &'static str,
+    pub cars: Vec<elem::Car>,
+}
+
+impl Scenario {
+    pub fn level(&self) -> level::Level {
+        level::Level::new(self.map.parse().expect("built-in scenario"), self.cars.to_vec())
+    }
+}
+
+pub fn all() -> Vec<Scenario> {
+    vec![
+        Scenario {
+            map: "\
+                x  r--R\n\
+                |  |   \n\
+                x--x   \
+            ",
+            cars: vec![
+                elem::Car::new(0, 0, elem::Color::Red),
+            ],
+        },
+        Scenario {
+            map: "\
+                x--B--x     x--B--x\n\
+                |     |     |     |\n\
+                x     x--B  x     x\n\
+                |           |     |\n\
+                x--x--x--x--x     x\n\
+                .                 |\n\
+                x--b--b--b--x--x--x\
+            ",
+            cars: vec![
+                elem::Car::new(0, 3, elem::Color::Blue),
+            ],
+        },
+        Scenario {
+            map: "\
+                R--x--x   \n\
+                .     |   \n\
+                .  x--x--x\n\
+                .  |  |  |\n\
+                .  r--x  x\n\
+                .        |\n\
+                x--x--x--x\n\
+            ",
+            cars: vec![
+                elem::Car::new(0, 3, elem::Color::Red),
+            ],
+        },
+        Scenario {
+            map: "x--r--R  B--b--x--x",
+            cars: vec![
+                elem::Car::new(0, 0, elem::Color::Red),
+                elem::Car::new(6, 0, elem::Color::Blue),
+            ],
+        },
+        Scenario {
+            map: "\
+                x--r--x--x--x\n\
+                .  |     |   \n\
+                B--x--x  b   \n\
+                .  |  |  |   \n\
+                R--x  x--x   \n\
+            ",
+            cars: vec![
+                elem::Car::new(0, 0, elem::Color::Red),
+                elem::Car::new(4, 0, elem::Color::Blue),
+            ],
+        },
+        Scenario {
+            map: "\
+                .     R      \n\
+                .     |      \n\
+                .     r      \n\
+                .     |      \n\
+                x--x--x--x--x\n\
+                .     |      \n\
+                .     b      \n\
+                .     |      \n\
+                .     B      \n\
+            ",
+            cars: vec![
+                elem::Car::new(0, 2, elem::Color::Red),
+                elem::Car::new(4, 2, elem::Color::Blue),
+            ],
+        },
+        Scenario {
+            map: "\
+                .  x--R--x--G--x   \n\
+                .  |     |     |   \n\
+                .  x--x--r--x--x   \n\
+                .  |     |     |   \n\
+                .  x--x--g--x--x   \n\
+                .  |     |     |   \n\
+                .  x     x     x   \n\
+                .  |     |     |   \n\
+                .  x     x     x   \n\
+                .  |     |     |   \n\
+                x--x--x--x--x--x--x\n\
+            ",
+            cars: vec![
+                elem::Car::new(0, 5, elem::Color::Red),
+                elem::Car::new(6, 5, elem::Color::Green),
+            ],
+        },
+        Scenario {
+            map: "\
+                .  x--x--r  x--x--x   \n\
+                .  |     |  |     |   \n\
+                R--x     x--x     x--x\n\
+                .  |     |  |     |   \n\
+                .  x--x--x  y--x--x   \n\
+                .     |        |      \n\
+                .  x--x--Y  x--x--x   \n\
+                .  |     |  |     |   \n\
+                .  x     x--x     x   \n\
+                .  |     |  |     |   \n\
+                .  x--x--x  x--x--x   \n\
+                .     |               \n\
+                .     x
+            ",
+            cars: vec![
+                elem::Car::new(2, 6, elem::Color::Red),
+                elem::Car::new(7, 1, elem::Color::Yellow),
+            ],
+        },
+        Scenario {
+            map: "\
+             x--x--x--x--x--x--x--x--x\n\
+             |           |           |\n\
+             x     b--B--x--x--b     x\n\
+             |     |     |     |     |\n\
+             x     x     x     x     x\n\
+             |     |     |     |     |\n\
+             x--B--x--x--x--x--x--x--b\n\
+             |     |     |     |     |\n\
+             x     x     x     x     x\n\
+             |     |     |     |     |\n\
+             x     x--x--x--B--x     x\n\
+             |           |           |\n\
+             x--x--x--x--x--x--x--x--x\
+            ",
+            cars: vec![
+                elem::Car::new(4, 3, elem::Color::Blue),
+            ],
+        },
+        Scenario {
+            map: "\
+            x--r--R      \n\
+            .            \n\
+            x--b--x--B   \n\
+            .            \n\
+            x--y--x--x--Y\
+            ",
+            cars: vec![
+                elem::Car::new(0, 0, elem::Color::Red),
+                elem::Car::new(0, 1, elem::Color::Blue),
+                elem::Car::new(0, 2, elem::Color::Yellow),
+            ],
+        },
+        Scenario {
+            map: "\
+            x--x--x--x--x--x--x--x\n\
+            |           |        |\n\
+            x           x        x\n\
+            |           |        |\n\
+            x--R--x--x--r--x--R--x\n\
+            |     |     |        |\n\
+            x     x     x        x\n\
+            |     |     |        |\n\
+            x     x     x--x--R--x\n\
+            |     |     |        |\n\
+            r--x--r     r        x\n\
+            |     |     |        |\n\
+            x     x     x--x--R--x\n\
+            |     |     |        |\n\
+            x     x     x        x\n\
+            |     |     |        |\n\
+            x--x--x--x--x--x--x--x\n\
+            .           |         \n\
+            .           x         \
+            ",
+            cars: vec![
+                elem::Car::new(4, 9, elem::Color::Red),
+            ],
+        },
+        Scenario {
+            map: "\
+            R--x--Y--x--B--x--x--x--x--x--x\n\
+            .                             |\n\
+            .                             x\n\
+            .                             |\n\
+            x--x--x--r--x--y--x--b--x--x--x\
+            ",
+            cars: vec![
+                elem::Car::new(0, 2, elem::Color::White),
+            ],
+        },
+        Scenario {
+            map: "\
+            .        x--x--~v-x--x         \n\
+            .        |           |         \n\
+            x--r--^v-x--x--#v-x--x--x--x--R\
+            ",
+            cars: vec![
+                elem::Car::new(0, 1, elem::Color::Red),
+            ],
+        },
+        Scenario {
+            map: "\
+            .           R            \n\
+            .           |            \n\
+            .           x            \n\
+            .           |            \n\
+            .           ~v           \n\
+            .           |            \n\
+            x--x--x--x--x--x--x--x--x\n\
+            |           |           |\n\
+            x           x           x\n\
+            |           |           |\n\
+            ^v          ~v          r\n\
+            |           |           |\n\
+            x           x           x\n\
+            |           |           |\n\
+            x--x--x--x--x--x--x--x--x\n\
+            .           |            \n\
+            .           x            \
+            ",
+            cars: vec![
+                elem::Car::new(4, 8, elem::Color::Red),
+            ],
+        },
+        Scenario {
+            map: "\
+            x--r--^p-x--R\n\
+            .            \n\
+            x--b--#p-x--B\n\
+            ",
+            cars: vec![
+                elem::Car::new(0, 1, elem::Color::Blue),
+                elem::Car::new(0, 0, elem::Color::Red),
+            ],
+        },
+        Scenario {
+            map: "\
+            x--r--^p-x--^o-vp-x--#p-~o-x--x--R\
+            ",
+            cars: vec![
+                elem::Car::new(0, 0, elem::Color::Red),
+            ],
+        },
+        Scenario {
+            map: "\
+            x--vo-x--x--x     x--x--x--x--x \n\
+            |     |     |     |           | \n\
+            x     y     x--#o-x           x \n\
+            |     |     |     |           | \n\
+            x--^o-x--x--x     x--x--G--x--x \n\
+            |     |     |     |     |     | \n\
+            ~o    ~o    ~o    ~v    ~o    ~v\n\
+            |     |     |     |     |     | \n\
+            x--^o-x--^o-x     x     x--vv-x \n\
+            |     |     |     |     |     | \n\
+            x     x     x--~o-x     ^o    g \n\
+            |     |     |     |     |     | \n\
+            x--Y--^o-x--x     x--x--r--x--x \n\
+            |     |     |     |     |     | \n\
+            #v    ~o    ~v    ~v    #o    #v \n\
+            |     |     |     |     |     | \n\
+            x--^v-x--x--x     x--x--x--x--x \n\
+            |     |     |     |           | \n\
+            x     x     x--~o-x           x \n\
+            |     |     |     |           | \n\
+            x--x--x--vv-x     x--x--R--x--x \
+            ",
+            cars: vec![
+                elem::Car::new(2, 9, elem::Color::White),
+            ],
+        },
+        Scenario {
+            map: "\
+            .        x--x--x     x--x\n\
+            .        |     |     |  |\n\
+            x--r--b--x  B--O--x  x  x\n\
+            .        |     |  |  |  |\n\
+            .        R--x--x  x--x  x\
+            ",
+            cars: vec![
+                elem::Car::new(0, 1, elem::Color::Red),
+                elem::Car::new(8, 2, elem::Color::Blue),
+            ],
+        },
+        Scenario {
+            map: "\
+            x                             x\n\
+            |                             |\n\
+            y                             r\n\
+            |                             |\n\
+            x     R--x--G--x--B--x--Y     x\n\
+            |     |  |  |  |  |  |  |     |\n\
+            x     x--O--O--O--O--O--x     x\n\
+            |     |  |  |  |  |  |  |     |\n\
+            x--x--O--O--O--O--O--O--O--x--x\n\
+            |  |  |  |  |  |  |  |  |  |  |\n\
+            x--O--O--O--O--O--O--O--O--O--x\n\
+            |  |  |  |  |  |  |  |  |  |  |\n\
+            x--x--O--O--O--O--O--O--O--x--x\n\
+            |     |  |  |  |  |  |  |     |\n\
+            x     x--O--O--O--O--O--x     x\n\
+            |     |  |  |  |  |  |  |     |\n\
+            x     x--x--x--x--x--x--x     x\n\
+            |                             |\n\
+            b                             g\n\
+            |                             |\n\
+            x                             x\
+            ",
+            cars: vec![
+                elem::Car::new(0, 0, elem::Color::Red),
+                elem::Car::new(0, 10, elem::Color::Green),
+                elem::Car::new(10, 0, elem::Color::Yellow),
+                elem::Car::new(10, 10, elem::Color::Blue),
+            ],
+        },
+    ]
+}
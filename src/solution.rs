@@ -0,0 +1,173 @@
+use std::fmt;
+use std::fs;
+use std::path;
+use std::str;
+use ::elem;
+use ::map;
+use ::solver;
+use ::canon;
+use ::level::ParseError;
+
+/// A solution as stored in solution files, next to the level it solves:
+///
+/// ```text
+/// # comments and blank lines are ignored
+/// level: 20d0ca6bfa28eb50
+/// layout: 76bddb5d45f7d690
+/// ruleset: 2
+/// targets: 01
+/// car: RRDDL..
+/// car: ..UURRR
+/// ```
+///
+/// `level` is the canonical hash of the level, `layout` the hash telling
+/// its rotations, reflections and car orders apart, `ruleset` the rules it
+/// was found under and `targets` whether each drop zone, in reading order,
+/// takes cubes (`1`) or gives them (`0`). Each `car` line holds the moves
+/// of a car, one letter per tick among `U`, `R`, `D` and `L`, `.` for
+/// waiting.
+pub struct SolutionFile {
+    pub level: u64,
+    pub layout: u64,
+    pub ruleset: u32,
+    pub solution: solver::Solution,
+    /// Header entries not understood by this module, in order.
+    pub header: Vec<(String, String)>,
+}
+
+impl SolutionFile {
+    pub fn new(map: &map::Map, cars: &[elem::Car], solution: solver::Solution) -> SolutionFile {
+        SolutionFile {
+            level: canon::hash(map, cars),
+            layout: canon::layout_hash(map, cars),
+            ruleset: solver::RULESET,
+            solution,
+            header: vec![],
+        }
+    }
+
+    pub fn load(path: &path::Path) -> Result<SolutionFile, String> {
+        let s = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        s.parse::<SolutionFile>().map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn save(&self, path: &path::Path) -> Result<(), String> {
+        fs::write(path, self.to_string()).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Check that the solution is for `map` and `cars` and solves them under
+    /// the current rules.
+    pub fn verify(&self, map: &map::Map, cars: &[elem::Car]) -> Result<(), String> {
+        let hash = canon::hash(map, cars);
+        if self.level != hash {
+            return Err(format!("solution of level {:016x}, not {:016x}", self.level, hash));
+        }
+        if self.layout != canon::layout_hash(map, cars) {
+            return Err("solution of a rotated, mirrored or reordered copy of the level".to_string());
+        }
+        if self.ruleset != solver::RULESET {
+            return Err(format!("solution under ruleset {}, not {}", self.ruleset, solver::RULESET));
+        }
+        if self.solution.dirs.iter().any(|moves| moves.len() != cars.len()) {
+            return Err(format!("moves for {} car(s), not {}", self.solution.dirs[0].len(), cars.len()));
+        }
+        let zones = map.iter().filter(|e| matches!(e.typ, elem::Type::DropOn | elem::Type::DropOff)).count();
+        if self.solution.targets.len() != zones {
+            return Err(format!("targets for {} drop zone(s), not {}", self.solution.targets.len(), zones));
+        }
+        let frames = solver::simulate(map, cars, &self.solution);
+        if frames.len() <= self.solution.dirs.len() {
+            return Err(format!("illegal move at tick {}", frames.len()));
+        }
+        if !frames.last().unwrap().0.is_solved() {
+            return Err("houses left after the last tick".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl str::FromStr for SolutionFile {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<SolutionFile, ParseError> {
+        let mut level = None;
+        let mut layout = None;
+        let mut ruleset = None;
+        let mut targets = None;
+        let mut cars: Vec<Vec<Option<elem::Dir>>> = vec![];
+        let mut header = vec![];
+        for (n, line) in s.lines().enumerate() {
+            let error = |message: &str| ParseError { line: n + 1, message: message.to_string() };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = match line.find(':') {
+                Some(i) => (line[..i].trim(), line[i + 1..].trim()),
+                None => return Err(error("expected `key: value`")),
+            };
+            match key {
+                "level" => level = Some(u64::from_str_radix(value, 16).map_err(|_| error("invalid level hash"))?),
+                "layout" => layout = Some(u64::from_str_radix(value, 16).map_err(|_| error("invalid layout hash"))?),
+                "ruleset" => ruleset = Some(value.parse().map_err(|_| error("invalid ruleset"))?),
+                "targets" => targets = Some(value.chars().map(|c| match c {
+                    '0' => Ok(false),
+                    '1' => Ok(true),
+                    _ => Err(error(&format!("invalid target `{}`", c))),
+                }).collect::<Result<Vec<bool>, ParseError>>()?),
+                "car" => {
+                    let moves = value.chars().map(|c| match c {
+                        'U' => Ok(Some(elem::Dir::Up)),
+                        'R' => Ok(Some(elem::Dir::Right)),
+                        'D' => Ok(Some(elem::Dir::Down)),
+                        'L' => Ok(Some(elem::Dir::Left)),
+                        '.' => Ok(None),
+                        _ => Err(error(&format!("invalid move `{}`", c))),
+                    }).collect::<Result<Vec<_>, ParseError>>()?;
+                    if cars.first().is_some_and(|first| first.len() != moves.len()) {
+                        return Err(error(&format!("expected {} moves", cars[0].len())));
+                    }
+                    cars.push(moves);
+                },
+                _ => header.push((key.to_string(), value.to_string())),
+            }
+        }
+        let missing = |key: &str| ParseError { line: s.lines().count(), message: format!("missing `{}:`", key) };
+        let ticks = cars.first().map_or(0, |moves| moves.len());
+        Ok(SolutionFile {
+            level: level.ok_or_else(|| missing("level"))?,
+            layout: layout.ok_or_else(|| missing("layout"))?,
+            ruleset: ruleset.ok_or_else(|| missing("ruleset"))?,
+            solution: solver::Solution {
+                dirs: (0..ticks).map(|t| cars.iter().map(|moves| moves[t]).collect()).collect(),
+                targets: targets.ok_or_else(|| missing("targets"))?,
+            },
+            header,
+        })
+    }
+}
+
+impl fmt::Display for SolutionFile {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        for (key, value) in self.header.iter() {
+            writeln!(fmt, "{}: {}", key, value)?;
+        }
+        writeln!(fmt, "level: {:016x}", self.level)?;
+        writeln!(fmt, "layout: {:016x}", self.layout)?;
+        writeln!(fmt, "ruleset: {}", self.ruleset)?;
+        let targets: String = self.solution.targets.iter().map(|&on| if on { '1' } else { '0' }).collect();
+        writeln!(fmt, "targets:{}{}", if targets.is_empty() { "" } else { " " }, targets)?;
+        let cars = self.solution.dirs.first().map_or(0, |moves| moves.len());
+        for i in 0..cars {
+            let moves: String = self.solution.dirs.iter().map(|moves| match moves[i] {
+                Some(elem::Dir::Up)    => 'U',
+                Some(elem::Dir::Right) => 'R',
+                Some(elem::Dir::Down)  => 'D',
+                Some(elem::Dir::Left)  => 'L',
+                None                   => '.',
+            }).collect();
+            writeln!(fmt, "car: {}", moves)?;
+        }
+        Ok(())
+    }
+}
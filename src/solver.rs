@@ -1,24 +1,54 @@
-use std::fmt;
+use std::collections::VecDeque;
+use std::iter;
 use ::map;
 use ::elem;
+use ::event;
+use ::game;
 
-#[derive(Clone)]
-pub struct State {
-    from: Vec<Option<elem::Dir>>,
-    cars: Vec<elem::Car>,
-    map: map::Map,
+/// Version of the rules of moves, to be bumped whenever they change so that
+/// solutions saved under older rules are not trusted.
+pub const RULESET: u32 = 2;
+
+/// Order in which `Solver::solve` explores the states.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Strategy {
+    DepthFirst,
+    /// Find a solution with the fewest ticks, at the cost of memory.
+    BreadthFirst,
 }
 
-impl fmt::Display for State {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        self.map.fmt(fmt)
+impl Strategy {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Strategy::DepthFirst   => "dfs",
+            Strategy::BreadthFirst => "bfs",
+        }
+    }
+
+    /// Inverse of `name`.
+    pub fn from_name(name: &str) -> Option<Strategy> {
+        [Strategy::DepthFirst, Strategy::BreadthFirst].iter().find(|s| s.name() == name).cloned()
     }
 }
 
 pub struct Solver {
-    states: Vec<State>,
-    targets: Vec<Vec<bool>>,
+    /// The moves tried so far are the ticks played.
+    game: game::Game,
+    /// Number of drop zones.
+    zones: usize,
+    /// The only drop zones taking cubes to try, rather than every choice.
+    targets: Option<Vec<bool>>,
     dirs: Vec<Vec<Option<elem::Dir>>>,
+    /// Moves imposed on every car for its first ticks.
+    prefix: Vec<Vec<elem::Dir>>,
+    /// States explored so far, and how many may be.
+    nodes: usize,
+    budget: Option<usize>,
+    /// States whose moves have been tried.
+    expanded: usize,
+    /// Most states held at once.
+    peak: usize,
+    strategy: Strategy,
 }
 
 impl Solver {
@@ -27,116 +57,325 @@ impl Solver {
             panic!("car {} is misplaced ({}, {})", car, car.coord.0, car.coord.1);
         }
         Solver {
-            targets: gen_onoff(m.iter().filter(|&elem| match elem.typ { elem::Type::DropOff => true, _ => false }).count()),
+            zones: m.iter().filter(|&elem| matches!(elem.typ, elem::Type::DropOff)).count(),
+            targets: None,
             dirs: gen_dirs(cars.len()),
-            states: vec![State{
-                from: vec![],
-                cars: cars,
-                map: m,
-            }],
+            prefix: vec![vec![]; cars.len()],
+            nodes: 0,
+            budget: None,
+            expanded: 0,
+            peak: 1,
+            strategy: Strategy::DepthFirst,
+            game: game::Game::new(m, cars),
+        }
+    }
+
+    /// Only look for solutions where the `i`-th car starts with `prefix[i]`.
+    pub fn with_prefix(m: map::Map, cars: Vec<elem::Car>, prefix: Vec<Vec<elem::Dir>>) -> Solver {
+        assert_eq!(prefix.len(), cars.len(), "one prefix per car");
+        let mut solver = Solver::new(m, cars);
+        solver.prefix = prefix;
+        solver
+    }
+
+    /// Give up once `nodes` states have been explored.
+    pub fn set_budget(&mut self, nodes: usize) {
+        self.budget = Some(nodes);
+    }
+
+    /// Only look for solutions where the drop zones, in reading order, take
+    /// cubes as `targets` says.
+    pub fn set_targets(&mut self, targets: Vec<bool>) {
+        assert_eq!(targets.len(), self.zones, "one target per drop zone");
+        self.targets = Some(targets);
+    }
+
+    pub fn set_strategy(&mut self, strategy: Strategy) {
+        self.strategy = strategy;
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        self.strategy
+    }
+
+    /// Number of states explored so far.
+    pub fn nodes(&self) -> usize {
+        self.nodes
+    }
+
+    /// Average number of legal moves from the states explored so far.
+    pub fn branching(&self) -> f64 {
+        if self.expanded == 0 {
+            return 0.0;
         }
+        self.nodes as f64 / self.expanded as f64
+    }
+
+    /// Most states kept in memory at once so far.
+    pub fn peak(&self) -> usize {
+        self.peak
+    }
+
+    /// True if the last search gave up before exploring every state.
+    pub fn exhausted(&self) -> bool {
+        self.budget.is_some_and(|budget| self.nodes >= budget)
     }
 
     pub fn solve(&mut self) -> Option<Solution> {
         // Try all combination of targets.
-        for targets in self.targets.to_vec() {
-            self.states.get_mut(0).unwrap().map.iter_mut()
-                                      .filter(|elem| match elem.typ { elem::Type::DropOff => true, _ => false })
-                                      .zip(&targets)
-                                      .map(|(e, &is_on)| { if is_on { e.typ = elem::Type::DropOn; } e })
-                                      .count();
-            let res = self.solve_inner();
+        for targets in self.target_choices() {
+            self.game.rewind();
+            self.game.set_targets(&targets);
+            let res = match self.strategy {
+                Strategy::DepthFirst => self.solve_inner(),
+                Strategy::BreadthFirst => self.solve_breadth_first(),
+            };
             if let Some(dirs) = res {
                 return Some(Solution {
-                    dirs: dirs,
-                    targets: targets,
+                    dirs,
+                    targets,
                 });
             }
+            if self.exhausted() {
+                return None;
+            }
         }
         None
     }
 
+    /// Call `f` on every solution, until the budget runs out.
+    pub fn each_solution(&mut self, f: &mut dyn FnMut(Solution)) {
+        for targets in self.target_choices() {
+            self.game.rewind();
+            self.game.set_targets(&targets);
+            self.each_solution_inner(&mut vec![], &targets, f);
+            if self.exhausted() {
+                return;
+            }
+        }
+    }
+
+    fn each_solution_inner(&mut self, dirs: &mut Vec<Vec<Option<elem::Dir>>>, targets: &[bool], f: &mut dyn FnMut(Solution)) {
+        self.expanded += 1;
+        let tick = self.game.tick();
+        for moves in self.dirs.to_vec().iter() {
+            if self.exhausted() {
+                return;
+            }
+            if !self.follows_prefix(tick, moves) || !self.push(moves) {
+                continue
+            }
+            dirs.push(moves.to_vec());
+            if self.is_solved() && self.prefix.iter().all(|p| p.len() <= tick + 1) {
+                f(Solution {
+                    dirs: dirs.clone(),
+                    targets: targets.to_vec(),
+                });
+            } else {
+                self.each_solution_inner(dirs, targets, f);
+            }
+            dirs.pop();
+            self.pop();
+        }
+    }
+
     fn solve_inner(&mut self) -> Option<Vec<Vec<Option<elem::Dir>>>> {
+        self.expanded += 1;
         let dirs = self.dirs.to_vec(); // avoid borrow
-        loop {
-            for moves in dirs.iter() {
-                // Try to move. Update states on success.
-                // Return false on failure.
-                if !self.push(moves) {
-                    continue
+        let tick = self.game.tick();
+        for moves in dirs.iter() {
+            if self.exhausted() {
+                return None;
+            }
+            if !self.follows_prefix(tick, moves) {
+                continue
+            }
+            // Try to move. Update states on success.
+            // Return false on failure.
+            if !self.push(moves) {
+                continue
+            }
+            if self.is_solved() && self.prefix.iter().all(|p| p.len() <= tick + 1) {
+                return Some(vec![moves.to_vec()]);
+            }
+            // Solve recursively.
+            if let Some(res) = self.solve_inner() {
+                // If a solution has been found, return.
+                let mut s = vec![moves.to_vec()];
+                s.extend(res);
+                return Some(s);
+            }
+            // Test other moves.
+            self.pop();
+        }
+        None
+    }
+
+    fn solve_breadth_first(&mut self) -> Option<Vec<Vec<Option<elem::Dir>>>> {
+        // Every state reached links to its parent, with the moves from it.
+        let mut links: Vec<(usize, Vec<Option<elem::Dir>>)> = vec![(0, vec![])];
+        let mut queue = VecDeque::new();
+        queue.push_back((0, 0, self.game.snapshot()));
+        while let Some((link, tick, state)) = queue.pop_front() {
+            self.expanded += 1;
+            for moves in self.dirs.to_vec().iter() {
+                if self.exhausted() {
+                    return None;
                 }
-                if self.is_solved() {
-                    return Some(vec![moves.to_vec()]);
+                if !self.follows_prefix(tick, moves) {
+                    continue
                 }
-                // Solve recursively.
-                if let Some(res) = self.solve_inner() {
-                    // If a solution has been found, return.
-                    let mut s = vec![moves.to_vec()];
-                    s.extend(res.into_iter());
-                    return Some(s);
+                let next = match state.next(moves, &mut ()) {
+                    Ok(next) => next,
+                    Err(_) => continue,
+                };
+                self.nodes += 1;
+                links.push((link, moves.to_vec()));
+                if next.map.is_solved() && self.prefix.iter().all(|p| p.len() <= tick + 1) {
+                    let mut dirs = vec![];
+                    let mut i = links.len() - 1;
+                    while i != 0 {
+                        dirs.push(links[i].1.clone());
+                        i = links[i].0;
+                    }
+                    dirs.reverse();
+                    return Some(dirs);
                 }
-                // Test other moves.
-                self.pop();
+                queue.push_back((links.len() - 1, tick + 1, next));
+                self.peak = self.peak.max(queue.len());
             }
-            return None
         }
+        None
     }
 
-    fn is_solved(&self) -> bool {
-        !self.states.last().unwrap().map.iter().any(|item| -> bool {
-            match item.typ {
-                elem::Type::House(_) => true,
-                _ => false,
-            }
+    /// The choices of drop zones to try, one at a time as there may be too
+    /// many to list.
+    fn target_choices(&self) -> Box<dyn Iterator<Item = Vec<bool>>> {
+        match self.targets {
+            Some(ref targets) => Box::new(iter::once(targets.clone())),
+            None => Box::new(iter::successors(Some(vec![false; self.zones]), |onoff| next_onoff(onoff))),
+        }
+    }
+
+    fn follows_prefix(&self, tick: usize, moves: &[Option<elem::Dir>]) -> bool {
+        self.prefix.iter().zip(moves.iter()).all(|(prefix, dir)| match prefix.get(tick) {
+            Some(&d) => *dir == Some(d),
+            None => true,
         })
     }
 
-    fn push(&mut self, moves: &Vec<Option<elem::Dir>>) -> bool {
-        // Check that if moves[i] == Some then last state's moves[i] != None
-        if !self.states.last().unwrap().from.iter().zip(moves.iter()).all(|(last_dir, dir)| -> bool {
-            if last_dir.is_none() {
-                return dir.is_none();
-            }
-            return true;
-        }) {
-            return false;
-        }
-        // Copy the last state
-        let mut state = self.states.last().unwrap().clone();
-        state.from = moves.to_vec();
-        // Move the cars
-        for (dir, car) in moves.into_iter().zip(state.cars.iter_mut()) {
-            if !state.map.move_car(car, *dir) {
-                return false;
-            }
-        }
-        // Check the coherency of the moves
-        if !state.map.check(&state.cars) {
+    fn is_solved(&self) -> bool {
+        self.game.is_won()
+    }
+
+    fn push(&mut self, moves: &[Option<elem::Dir>]) -> bool {
+        if self.game.step_events(moves, &mut ()).is_err() {
             return false;
         }
-
-        self.states.push(state);
+        self.nodes += 1;
+        self.peak = self.peak.max(self.game.tick() + 1);
         true
     }
 
     fn pop(&mut self) {
-        self.states.pop();
+        self.game.undo();
+    }
+}
+
+/// Play `solution` from the start and return the map and cars at every
+/// tick, the first entry being the starting position. Stop early if the
+/// solution turns out to be illegal.
+pub fn simulate(map: &map::Map, cars: &[elem::Car], solution: &Solution) -> Vec<(map::Map, Vec<elem::Car>)> {
+    let mut game = game::Game::new(map.clone(), cars.to_vec());
+    game.set_targets(&solution.targets);
+
+    let mut frames = vec![(game.map().clone(), game.cars().to_vec())];
+    for moves in solution.dirs.iter() {
+        if game.step_events(moves, &mut ()).is_err() {
+            break;
+        }
+        frames.push((game.map().clone(), game.cars().to_vec()));
+    }
+    frames
+}
+
+/// The events of every tick of `solution`, stopping after the first
+/// illegal one.
+pub fn events(map: &map::Map, cars: &[elem::Car], solution: &Solution) -> Vec<Vec<event::Event>> {
+    let mut game = game::Game::new(map.clone(), cars.to_vec());
+    game.set_targets(&solution.targets);
+    let mut ticks = vec![];
+    for moves in solution.dirs.iter() {
+        let mut events = vec![];
+        let legal = game.step_events(moves, &mut events).is_ok();
+        ticks.push(events);
+        if !legal {
+            break;
+        }
     }
+    ticks
 }
 
+/// Whether `solution` is legal and delivers every cube of `map`.
+pub fn verify(map: &map::Map, cars: &[elem::Car], solution: &Solution) -> bool {
+    let zones = map.iter().filter(|e| matches!(e.typ, elem::Type::DropOn | elem::Type::DropOff)).count();
+    if solution.targets.len() != zones || solution.dirs.iter().any(|moves| moves.len() != cars.len()) {
+        return false;
+    }
+    let frames = simulate(map, cars, solution);
+    frames.len() == solution.dirs.len() + 1 && frames.last().unwrap().0.is_solved()
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Solution {
     pub dirs: Vec<Vec<Option<elem::Dir>>>,
     pub targets: Vec<bool>,
 }
 
+impl Solution {
+    /// The state of the level at every tick of the solution, the first entry
+    /// being the starting position. Stop early if the solution turns out to
+    /// be illegal.
+    pub fn timeline(&self, map: &map::Map, cars: &[elem::Car]) -> Vec<Tick> {
+        let frames = simulate(map, cars, self);
+        frames.iter().enumerate().map(|(tick, (map, cars))| {
+            let cells = (0..map.height).flat_map(|y| (0..map.width).map(move |x| (x, y)));
+            Tick {
+                cars: cars.clone(),
+                changes: match tick {
+                    0 => vec![],
+                    _ => cells.clone().filter(|&coord| map[coord].typ != frames[tick - 1].0[coord].typ)
+                                      .map(|coord| (coord, map[coord].typ))
+                                      .collect(),
+                },
+                switches: cells.map(|coord| (coord, map[coord].typ))
+                               .filter(|&(_, typ)| matches!(typ, elem::Type::ArmedButton(_) | elem::Type::PushedButton(_) |
+                                                                 elem::Type::OpenBridge(_) | elem::Type::ClosedBridge(_)))
+                               .collect(),
+            }
+        }).collect()
+    }
+}
+
+/// The state of a level after a tick of a solution.
+#[derive(Clone, Debug)]
+pub struct Tick {
+    /// Where every car stands and what it carries, the top cube last.
+    pub cars: Vec<elem::Car>,
+    /// The cells whose type changed during the tick, with their new type.
+    pub changes: Vec<((usize, usize), elem::Type)>,
+    /// Every button and bridge, in reading order.
+    pub switches: Vec<((usize, usize), elem::Type)>,
+}
+
 /// Generate all possible directions given a number of cars.
-/// e.g. gen_dirs(1) => [[Up], [Right], [Down], [Left]]
+/// e.g. gen_dirs(1) => [[Up], [Right], [Down], [Left]], gen_dirs(0) => []
 fn gen_dirs(n: usize) -> Vec<Vec<Option<elem::Dir>>> {
     gen_dirs_inner(n).into_iter().filter(|dirs| !dirs.iter().all(|d| d.is_none())).collect()
 }
 
 fn gen_dirs_inner(n: usize) -> Vec<Vec<Option<elem::Dir>>> {
-    let dirs = vec![
+    let dirs = [
         Some(elem::Dir::Left),
         Some(elem::Dir::Up),
         Some(elem::Dir::Right),
@@ -145,10 +384,8 @@ fn gen_dirs_inner(n: usize) -> Vec<Vec<Option<elem::Dir>>> {
     ];
 
     let mut v: Vec<Vec<_>> = vec![];
-    if n == 1 {
-        for dir in dirs.into_iter() {
-            v.push(vec![dir]);
-        }
+    if n == 0 {
+        v.push(vec![]);
     } else {
         let dirs_n1 = gen_dirs_inner(n-1);
         for dir_n1 in dirs_n1 {
@@ -162,20 +399,16 @@ fn gen_dirs_inner(n: usize) -> Vec<Vec<Option<elem::Dir>>> {
     v
 }
 
-fn gen_onoff(n: usize) -> Vec<Vec<bool>> {
-    let mut v: Vec<Vec<_>> = vec![];
-    if n == 0 {
-        return vec![vec![]];
-    } else {
-        let onoffs_n1 = gen_onoff(n-1);
-        for onoff_n1 in onoffs_n1 {
-            let mut c = onoff_n1.to_vec();
-            c.push(false);
-            v.push(c);
-            let mut c = onoff_n1.to_vec();
-            c.push(true);
-            v.push(c);
+/// The combination of drop zones being on or off after `onoff`, counting in
+/// binary with the first zone as the most significant bit.
+/// e.g. next_onoff([false, true]) => Some([true, false]), next_onoff([true, true]) => None
+fn next_onoff(onoff: &[bool]) -> Option<Vec<bool>> {
+    let mut next = onoff.to_vec();
+    for on in next.iter_mut().rev() {
+        *on = !*on;
+        if *on {
+            return Some(next);
         }
     }
-    v
+    None
 }
@@ -0,0 +1,173 @@
+use std::fmt::Write;
+use ::map;
+use ::elem;
+use ::solver;
+use ::report;
+
+/// Side of a cell, in pixels.
+pub const CELL: usize = 48;
+
+pub fn hex(color: elem::Color) -> &'static str {
+    match color {
+        elem::Color::Red    => "#e41a1c",
+        elem::Color::Green  => "#4daf4a",
+        elem::Color::Blue   => "#377eb8",
+        elem::Color::Yellow => "#f2c80f",
+        elem::Color::White  => "#f4f4f4",
+        elem::Color::Orange => "#ff7f00",
+        elem::Color::Violet => "#984ea3",
+        elem::Color::Pink   => "#f781bf",
+        elem::Color::Cream  => "#e6d5a8",
+    }
+}
+
+/// Center of a cell, in pixels.
+pub fn center((x, y): (usize, usize)) -> (usize, usize) {
+    (x * CELL + CELL / 2, y * CELL + CELL / 2)
+}
+
+/// Render `map` as a standalone SVG document. When a solution is given, the
+/// chosen drop zones are highlighted and the path of every car is drawn on
+/// top of the map with arrows and tick numbers.
+pub fn render(map: &map::Map, cars: &[elem::Car], solution: Option<&solver::Solution>) -> String {
+    let mut s = String::new();
+    let (w, h) = (map.width * CELL, map.height * CELL);
+    writeln!(s, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" font-family=\"sans-serif\">", w, h, w, h).unwrap();
+    s.push_str(&defs(cars));
+    writeln!(s, "<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>", w, h).unwrap();
+    s.push_str(&grid(map));
+
+    let report = solution.map(|solution| report::Report::new(map, cars, solution));
+    s.push_str(&body(map, report.as_ref().map_or(&[][..], |r| &r.targets[..])));
+    if let Some(ref report) = report {
+        s.push_str(&paths(report));
+    }
+    for (i, car) in cars.iter().enumerate() {
+        s.push_str(&truck(i, car));
+    }
+    s.push_str("</svg>\n");
+    s
+}
+
+/// Arrow heads, one per car colour.
+fn defs(cars: &[elem::Car]) -> String {
+    let mut s = String::from("<defs>\n");
+    for (i, car) in cars.iter().enumerate() {
+        writeln!(s, "<marker id=\"arrow{}\" viewBox=\"0 0 10 10\" refX=\"5\" refY=\"5\" markerWidth=\"5\" markerHeight=\"5\" orient=\"auto\">\
+                     <path d=\"M0,0 L10,5 L0,10 z\" fill=\"{}\" stroke=\"#333333\" stroke-width=\"1\"/></marker>",
+                 i, hex(car.color)).unwrap();
+    }
+    s.push_str("</defs>\n");
+    s
+}
+
+fn grid(map: &map::Map) -> String {
+    let mut s = String::from("<g stroke=\"#eeeeee\" stroke-width=\"1\">\n");
+    for x in 0..map.width + 1 {
+        writeln!(s, "<line x1=\"{0}\" y1=\"0\" x2=\"{0}\" y2=\"{1}\"/>", x * CELL, map.height * CELL).unwrap();
+    }
+    for y in 0..map.height + 1 {
+        writeln!(s, "<line x1=\"0\" y1=\"{0}\" x2=\"{1}\" y2=\"{0}\"/>", y * CELL, map.width * CELL).unwrap();
+    }
+    s.push_str("</g>\n");
+    s
+}
+
+/// Roads then cells.
+fn body(map: &map::Map, targets: &[(usize, usize)]) -> String {
+    let mut s = String::from("<g stroke=\"#999999\" stroke-width=\"8\" stroke-linecap=\"round\">\n");
+    for y in 0..map.height {
+        for x in 0..map.width {
+            let (cx, cy) = center((x, y));
+            if map[(x, y)].connected(elem::Dir::Right) {
+                writeln!(s, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>", cx, cy, cx + CELL, cy).unwrap();
+            }
+            if map[(x, y)].connected(elem::Dir::Down) {
+                writeln!(s, "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>", cx, cy, cx, cy + CELL).unwrap();
+            }
+        }
+    }
+    s.push_str("</g>\n<g stroke=\"#333333\" stroke-width=\"1.5\">\n");
+    for y in 0..map.height {
+        for x in 0..map.width {
+            writeln!(s, "<g id=\"cell-{}-{}\">{}</g>", x, y, cell(map[(x, y)].typ, (x, y), targets.contains(&(x, y)))).unwrap();
+        }
+    }
+    s.push_str("</g>\n");
+    s
+}
+
+pub fn cell(typ: elem::Type, coord: (usize, usize), target: bool) -> String {
+    let (cx, cy) = center(coord);
+    let q = CELL / 4;
+    match typ {
+        elem::Type::Empty => String::new(),
+        elem::Type::Road => format!("<circle cx=\"{}\" cy=\"{}\" r=\"5\" fill=\"#999999\" stroke=\"none\"/>", cx, cy),
+        elem::Type::DropOn | elem::Type::DropOff => {
+            let fill = if target || matches!(typ, elem::Type::DropOn) { hex(elem::Color::Yellow) } else { "#ffffff" };
+            format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke-dasharray=\"4 3\"/>", cx, cy, q, fill)
+        },
+        elem::Type::Cube(c) => format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                                       cx - q, cy - q, 2 * q, 2 * q, hex(c)),
+        elem::Type::House(c) | elem::Type::FullHouse(c) => {
+            let mut s = format!("<polygon points=\"{},{} {},{} {},{} {},{} {},{}\" fill=\"{}\"/>",
+                                cx, cy - q - 4, cx + q + 2, cy - 2, cx + q, cy + q, cx - q, cy + q, cx - q - 2, cy - 2, hex(c));
+            if let elem::Type::FullHouse(_) = typ {
+                write!(s, "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>",
+                       cx - q / 2, cy, q, q / 2 + 2).unwrap();
+            }
+            s
+        },
+        elem::Type::ArmedButton(c) => format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke-width=\"4\"/>",
+                                              cx, cy, q - 2, hex(c)),
+        elem::Type::PushedButton(c) => format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke-width=\"1\"/>",
+                                               cx, cy, q / 2 + 2, hex(c)),
+        elem::Type::ClosedBridge(c) => format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                                               cx - q - 4, cy - q - 4, 2 * q + 8, 2 * q + 8, hex(c)),
+        elem::Type::OpenBridge(c) => format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#ffffff\" stroke=\"{}\" stroke-width=\"3\" stroke-dasharray=\"6 4\"/>",
+                                             cx - q - 4, cy - q - 4, 2 * q + 8, 2 * q + 8, hex(c)),
+    }
+}
+
+fn truck(index: usize, car: &elem::Car) -> String {
+    let (cx, cy) = center(car.coord);
+    let q = CELL / 4;
+    format!("<g id=\"truck-{}\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"4\" fill=\"{}\" stroke=\"#333333\" stroke-width=\"2\"/>\
+             <text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" fill=\"#000000\">{}</text></g>\n",
+            index, cx - q - 2, cy - q / 2 - 2, 2 * q + 4, q + 4, hex(car.color),
+            cx, cy + q / 2 - 1, q, index)
+}
+
+/// The path of every car, slightly shifted so that cars using the same
+/// road stay apart, with the tick at which each cell is reached.
+fn paths(report: &report::Report) -> String {
+    let mut s = String::new();
+    let n = report.cars.len() as isize;
+    for (i, car) in report.cars.iter().enumerate() {
+        let offset = (2 * i as isize - (n - 1)) * 3;
+        let point = |coord| {
+            let (x, y) = center(coord);
+            (x as isize + offset, y as isize + offset)
+        };
+        let mut points = String::new();
+        for (tick, &coord) in car.positions.iter().enumerate() {
+            if tick > 0 && coord == car.positions[tick - 1] {
+                continue;
+            }
+            let (x, y) = point(coord);
+            write!(points, "{},{} ", x, y).unwrap();
+        }
+        writeln!(s, "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"4\" stroke-opacity=\"0.85\" \
+                     stroke-linejoin=\"round\" marker-mid=\"url(#arrow{})\" marker-end=\"url(#arrow{})\"/>",
+                 points.trim_end(), hex(car.color), i, i).unwrap();
+        for (tick, &coord) in car.positions.iter().enumerate().skip(1) {
+            if coord == car.positions[tick - 1] {
+                continue;
+            }
+            let (x, y) = point(coord);
+            writeln!(s, "<text x=\"{}\" y=\"{}\" font-size=\"10\" fill=\"#000000\" stroke=\"#ffffff\" stroke-width=\"2\" \
+                         paint-order=\"stroke\">{}</text>", x + 6, y - 6, tick).unwrap();
+        }
+    }
+    s
+}
@@ -0,0 +1,79 @@
+use std::io::{self, IsTerminal};
+use std::process;
+
+/// A key press read from a terminal in non canonical mode.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Tab,
+    Backspace,
+    Char(char),
+}
+
+/// Put the terminal in non canonical mode for the lifetime of the value so
+/// that keys are read as soon as they are pressed.
+pub struct RawMode {
+    saved: Option<String>,
+}
+
+impl RawMode {
+    pub fn new() -> RawMode {
+        if !io::stdin().is_terminal() {
+            return RawMode { saved: None };
+        }
+        let saved = process::Command::new("stty").arg("-g").stdin(process::Stdio::inherit()).output().ok()
+                                                 .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string());
+        let _ = process::Command::new("stty").args(["-icanon", "-echo", "min", "1"]).stdin(process::Stdio::inherit()).status();
+        RawMode { saved }
+    }
+}
+
+impl Default for RawMode {
+    fn default() -> RawMode {
+        RawMode::new()
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        if let Some(ref saved) = self.saved {
+            let _ = process::Command::new("stty").arg(saved).stdin(process::Stdio::inherit()).status();
+        }
+    }
+}
+
+/// Decode the next key from raw terminal input. Unknown escape sequences
+/// are skipped.
+pub fn read_key(input: &mut dyn Iterator<Item = u8>) -> Option<Key> {
+    loop {
+        return Some(match input.next()? {
+            0x1b => match (input.next()?, input.next()?) {
+                (b'[', b'A') => Key::Up,
+                (b'[', b'B') => Key::Down,
+                (b'[', b'C') => Key::Right,
+                (b'[', b'D') => Key::Left,
+                _ => continue,
+            },
+            b'\n' | b'\r' => Key::Enter,
+            b'\t' => Key::Tab,
+            0x7f | 0x08 => Key::Backspace,
+            c if c.is_ascii() => Key::Char(c as char),
+            _ => continue,
+        });
+    }
+}
+
+/// Escape sequence clearing the screen when stdout is a terminal, so that
+/// piped output stays readable.
+pub fn clear() -> &'static str {
+    if io::stdout().is_terminal() {
+        // Clear the screen and move to the top left corner.
+        "\x1b[2J\x1b[H"
+    } else {
+        ""
+    }
+}
@@ -0,0 +1,121 @@
+use ::vec2d;
+use ::elem;
+use ::map;
+use ::solver;
+
+/// A rectangle of cells.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+/// A change of the geometry of a map, applied alike to its cars and to the
+/// solutions played on it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Transform {
+    /// A quarter turn clockwise.
+    Rotate90,
+    /// Swap left and right.
+    MirrorHorizontal,
+    /// Swap top and bottom.
+    MirrorVertical,
+    /// Keep the cells of the rectangle, cutting the roads leaving it.
+    Crop(Rect),
+    /// Surround with that many empty rows and columns on every side.
+    Pad(usize),
+}
+
+impl Transform {
+    /// Size of a `width` x `height` map once transformed.
+    pub fn size(&self, (width, height): (usize, usize)) -> (usize, usize) {
+        match *self {
+            Transform::Rotate90 => (height, width),
+            Transform::MirrorHorizontal | Transform::MirrorVertical => (width, height),
+            Transform::Crop(r) => ((r.x + r.width).min(width).saturating_sub(r.x),
+                                   (r.y + r.height).min(height).saturating_sub(r.y)),
+            Transform::Pad(n) => (width + 2 * n, height + 2 * n),
+        }
+    }
+
+    /// Where the cell at `(x, y)` of a `width` x `height` map goes, if it is
+    /// kept.
+    pub fn coord(&self, (x, y): (usize, usize), (width, height): (usize, usize)) -> Option<(usize, usize)> {
+        match *self {
+            Transform::Rotate90 => Some((height - 1 - y, x)),
+            Transform::MirrorHorizontal => Some((width - 1 - x, y)),
+            Transform::MirrorVertical => Some((x, height - 1 - y)),
+            Transform::Crop(r) if x >= r.x && y >= r.y && x < r.x + r.width && y < r.y + r.height => Some((x - r.x, y - r.y)),
+            Transform::Crop(_) => None,
+            Transform::Pad(n) => Some((x + n, y + n)),
+        }
+    }
+
+    pub fn dir(&self, dir: elem::Dir) -> elem::Dir {
+        match (*self, dir) {
+            (Transform::Rotate90, elem::Dir::Up) => elem::Dir::Right,
+            (Transform::Rotate90, elem::Dir::Right) => elem::Dir::Down,
+            (Transform::Rotate90, elem::Dir::Down) => elem::Dir::Left,
+            (Transform::Rotate90, elem::Dir::Left) => elem::Dir::Up,
+            (Transform::MirrorHorizontal, elem::Dir::Left) | (Transform::MirrorHorizontal, elem::Dir::Right) => dir.rev(),
+            (Transform::MirrorVertical, elem::Dir::Up) | (Transform::MirrorVertical, elem::Dir::Down) => dir.rev(),
+            _ => dir,
+        }
+    }
+
+    pub fn map(&self, map: &map::Map) -> map::Map {
+        let size = (map.width, map.height);
+        let (width, height) = self.size(size);
+        let mut m: map::Map = vec2d::Vec2D::new(width, height);
+        for y in 0..map.height {
+            for x in 0..map.width {
+                if let Some(coord) = self.coord((x, y), size) {
+                    let e = &map[(x, y)];
+                    let mut conn = [false; 4];
+                    for &dir in [elem::Dir::Up, elem::Dir::Right, elem::Dir::Down, elem::Dir::Left].iter() {
+                        conn[self.dir(dir) as usize] = e.connected(dir);
+                    }
+                    m[coord] = elem::Elem { conn, ..*e };
+                }
+            }
+        }
+        if width == 0 || height == 0 {
+            return m;
+        }
+        // Cut the roads leaving a cropped map.
+        for y in 0..height {
+            m[(0, y)].disconnect(elem::Dir::Left);
+            m[(width - 1, y)].disconnect(elem::Dir::Right);
+        }
+        for x in 0..width {
+            m[(x, 0)].disconnect(elem::Dir::Up);
+            m[(x, height - 1)].disconnect(elem::Dir::Down);
+        }
+        m
+    }
+
+    /// The cars of `map` once transformed, or None if one is cropped out.
+    pub fn cars(&self, map: &map::Map, cars: &[elem::Car]) -> Option<Vec<elem::Car>> {
+        cars.iter().map(|car| {
+            self.coord(car.coord, (map.width, map.height)).map(|coord| elem::Car { coord, ..car.clone() })
+        }).collect()
+    }
+
+    /// The moves of `solution` on `map` once transformed. Drop zones are
+    /// listed in reading order, which the transform may change.
+    pub fn solution(&self, map: &map::Map, solution: &solver::Solution) -> solver::Solution {
+        let size = (map.width, map.height);
+        let mut zones: Vec<((usize, usize), bool)> = (0..map.height).flat_map(|y| (0..map.width).map(move |x| (x, y)))
+            .filter(|&coord| matches!(map[coord].typ, elem::Type::DropOn | elem::Type::DropOff))
+            .zip(solution.targets.iter().cloned())
+            .filter_map(|(coord, on)| self.coord(coord, size).map(|coord| (coord, on)))
+            .collect();
+        zones.sort_by_key(|&((x, y), _)| (y, x));
+        solver::Solution {
+            dirs: solution.dirs.iter().map(|moves| moves.iter().map(|dir| dir.map(|d| self.dir(d))).collect()).collect(),
+            targets: zones.into_iter().map(|(_, on)| on).collect(),
+        }
+    }
+}
@@ -14,17 +14,17 @@ pub struct Vec2D<T> {
 impl<T: Default + Clone> Vec2D<T> {
     pub fn new(width: usize, height: usize) -> Vec2D<T> {
         Vec2D {
-            width: width,
-            height: height,
+            width,
+            height,
             undl: vec![T::default(); width * height],
         }
     }
 
-    pub fn iter(&self) -> slice::Iter<T> {
+    pub fn iter(&self) -> slice::Iter<'_, T> {
         self.undl.iter()
     }
 
-    pub fn iter_mut(&mut self) -> slice::IterMut<T> {
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, T> {
         self.undl.iter_mut()
     }
 }
@@ -33,9 +33,9 @@ impl<T: fmt::Debug> fmt::Debug for Vec2D<T> {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
         for (r, row) in self.undl.chunks(self.width).enumerate() {
             if r > 0 {
-                try!(write!(fmt, "\n"));
+                writeln!(fmt)?;
             }
-            try!(write!(fmt, "{:?}", row));
+            write!(fmt, "{:?}", row)?;
         }
         Result::Ok(())
     }
@@ -0,0 +1,71 @@
+extern crate rgb_express_solver;
+
+mod common;
+
+use std::env;
+use std::fs;
+use rgb_express_solver::cache::Cache;
+use rgb_express_solver::transform::Transform;
+use rgb_express_solver::{scenario, solver};
+
+/// A cache of its own for every test, as they run in parallel.
+fn cache(name: &str) -> Cache {
+    let cache = Cache::new(env::temp_dir().join(format!("rgb-express-cache-{}-{}", name, std::process::id())));
+    cache.clear().unwrap();
+    cache
+}
+
+#[test]
+fn symmetric_levels_share_their_solution() {
+    let cache = cache("symmetric");
+    let strategy = solver::Strategy::DepthFirst;
+    for (n, level, solution) in common::solved() {
+        cache.put(&level.map, &level.cars, strategy, &solution).unwrap();
+        assert_eq!(cache.get(&level.map, &level.cars, strategy), Some(solution), "scenario {}", n);
+
+        for t in [Transform::Rotate90, Transform::MirrorVertical, Transform::Pad(1)] {
+            let map = t.map(&level.map);
+            let cars = t.cars(&level.map, &level.cars).unwrap();
+            assert!(cache.get(&map, &cars, strategy).is_some(), "scenario {}, {:?}", n, t);
+        }
+        // Cars move all at once, so their order does not matter either.
+        let mut cars = level.cars.clone();
+        cars.reverse();
+        let solution = cache.get(&level.map, &cars, strategy);
+        assert!(solution.is_some_and(|solution| solver::verify(&level.map, &cars, &solution)), "scenario {}, cars reversed", n);
+        assert!(cache.get(&level.map, &level.cars, solver::Strategy::BreadthFirst).is_none());
+    }
+    cache.clear().unwrap();
+}
+
+#[test]
+fn wrong_entries_are_dropped() {
+    let cache = cache("wrong");
+    let level = scenario::all()[1].level();
+    let strategy = solver::Strategy::DepthFirst;
+    let mut solution = solver::Solver::new(level.map.clone(), level.cars.clone()).solve().unwrap();
+    solution.dirs.pop();
+    cache.put(&level.map, &level.cars, strategy, &solution).unwrap();
+    assert_eq!(cache.stats().unwrap().entries, 1);
+    assert!(cache.get(&level.map, &level.cars, strategy).is_none());
+    assert_eq!(cache.stats().unwrap().entries, 0);
+    cache.clear().unwrap();
+}
+
+#[test]
+fn stats_and_clear() {
+    let cache = cache("stats");
+    fs::create_dir_all(cache.dir()).unwrap();
+    fs::write(cache.dir().join("notes"), "not an entry").unwrap();
+    for n in 0..3 {
+        let level = scenario::all()[n].level();
+        let mut s = solver::Solver::new(level.map.clone(), level.cars.clone());
+        assert!(cache.solve(&mut s, &level.map, &level.cars).is_some());
+    }
+    let stats = cache.stats().unwrap();
+    assert_eq!((stats.entries, stats.stale), (3, 0));
+    assert!(stats.bytes > 0);
+    assert_eq!(cache.clear().unwrap(), 3);
+    assert_eq!(cache.stats().unwrap().entries, 0);
+    fs::remove_dir_all(cache.dir()).unwrap();
+}
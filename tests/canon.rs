@@ -0,0 +1,59 @@
+extern crate rgb_express_solver;
+
+use rgb_express_solver::level::Level;
+use rgb_express_solver::transform::Transform;
+use rgb_express_solver::{canon, scenario};
+
+#[test]
+fn hash_is_invariant_under_symmetries_and_padding() {
+    for (n, scenario) in scenario::all().into_iter().enumerate() {
+        let level = scenario.level();
+        let expected = canon::hash(&level.map, &level.cars);
+        for &ts in [&[Transform::Rotate90][..],
+                    &[Transform::Rotate90, Transform::Rotate90],
+                    &[Transform::MirrorHorizontal],
+                    &[Transform::MirrorVertical, Transform::Rotate90],
+                    &[Transform::Pad(3)]].iter() {
+            let (mut map, mut cars) = (level.map.clone(), level.cars.clone());
+            for t in ts {
+                cars = t.cars(&map, &cars).unwrap();
+                map = t.map(&map);
+            }
+            cars.reverse();
+            assert_eq!(canon::hash(&map, &cars), expected, "scenario {}, {:?}", n, ts);
+        }
+    }
+}
+
+#[test]
+fn hash_ignores_layout_of_the_file() {
+    let a: Level = "car: 0 0 red\nmap:\nx--r--R\n".parse().unwrap();
+    let b: Level = "# same level\ncar: 1 1 red\n\nmap:\n.  .\n \n.  x--r--R   \n \n \n".parse().unwrap();
+    assert_eq!(canon::level_hash(&a), canon::level_hash(&b));
+}
+
+#[test]
+fn hash_ignores_the_order_of_the_cars() {
+    let a: Level = "car: 0 0 red\ncar: 1 0 blue\nmap:\nx--x\n".parse().unwrap();
+    let b: Level = "car: 1 0 blue\ncar: 0 0 red\nmap:\nx--x\n".parse().unwrap();
+    assert_eq!(canon::level_hash(&a), canon::level_hash(&b));
+}
+
+#[test]
+fn built_in_scenarios_are_distinct() {
+    let mut hashes: Vec<u64> = scenario::all().iter().map(|s| {
+        let level = s.level();
+        canon::hash(&level.map, &level.cars)
+    }).collect();
+    let n = hashes.len();
+    hashes.sort();
+    hashes.dedup();
+    assert_eq!(hashes.len(), n);
+}
+
+#[test]
+fn hash_is_stable() {
+    let level: Level = "car: 0 0 red\nmap:\nx--r--R\n".parse().unwrap();
+    assert_eq!(canon::canonical(&level.map, &level.cars), "car: 0 0 red\nmap:\nx \n| \nr \n| \nR \n");
+    assert_eq!(canon::level_hash(&level), "20d0ca6bfa28eb50");
+}
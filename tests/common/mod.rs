@@ -0,0 +1,19 @@
+//! Helpers shared by the integration tests.
+
+use rgb_express_solver::{level, scenario, solver};
+
+/// Too many drop zones to be searched in a test.
+pub const TOO_SLOW: [usize; 1] = [18];
+
+/// The built-in scenarios the solver finds a solution to, with their number,
+/// but the slow ones.
+pub fn solved() -> Vec<(usize, level::Level, solver::Solution)> {
+    scenario::all().into_iter().enumerate()
+                   .filter(|&(n, _)| !TOO_SLOW.contains(&n))
+                   .filter_map(|(n, scenario)| {
+                       let level = scenario.level();
+                       let solution = solver::Solver::new(level.map.clone(), level.cars.clone()).solve()?;
+                       Some((n, level, solution))
+                   })
+                   .collect()
+}
@@ -0,0 +1,81 @@
+extern crate rgb_express_solver;
+
+mod common;
+
+use rgb_express_solver::elem::{Car, Color, Dir, Type};
+use rgb_express_solver::event::Event;
+use rgb_express_solver::game::{Game, Snapshot};
+use rgb_express_solver::map::{Map, MoveError};
+use rgb_express_solver::solver;
+
+/// Play `moves` on `map` after `from`, updating `cars` if they are legal.
+fn tick(map: &str, cars: &mut [Car], from: &[Option<Dir>], moves: &[Option<Dir>]) -> (Vec<Event>, Result<(), (usize, MoveError)>) {
+    let mut map = map.parse::<Map>().unwrap();
+    map.set_targets(&[true]);
+    let mut game = Game::from_snapshot(Snapshot { map, cars: cars.to_vec(), from: from.to_vec() });
+    let mut events = vec![];
+    let result = game.step_events(moves, &mut events);
+    cars.clone_from_slice(game.cars());
+    (events, result)
+}
+
+#[test]
+fn cargo_events() {
+    let mut cars = vec![Car::new(0, 0, Color::Red)];
+    let right = [Some(Dir::Right)];
+    let (events, _) = tick("x--r--x", &mut cars, &[], &right);
+    assert_eq!(events, [Event::Moved { car: 0, from: (0, 0), to: (1, 0), dir: Dir::Right },
+                        Event::PickedUp { car: 0, cube: Color::Red, coord: (1, 0) }]);
+
+    cars[0].coord = (0, 0);
+    let (events, _) = tick("x--R--x", &mut cars, &[], &right);
+    assert_eq!(events[1], Event::Delivered { car: 0, house: Color::Red, coord: (1, 0) });
+
+    let mut cars = vec![Car::new(0, 0, Color::Red)];
+    cars[0].cubes = vec![Type::Cube(Color::Blue)];
+    let (events, _) = tick("x--O--x", &mut cars, &[], &right);
+    assert_eq!(events[1], Event::Dropped { car: 0, cube: Color::Blue, coord: (1, 0) });
+}
+
+#[test]
+fn button_events() {
+    let mut cars = vec![Car::new(0, 0, Color::Red)];
+    let (events, _) = tick("x--^p-~p-#p-~v", &mut cars, &[], &[Some(Dir::Right)]);
+    assert_eq!(events[1..], [Event::ButtonPressed { car: 0, color: Color::Pink, coord: (1, 0) },
+                             Event::BridgeClosed { color: Color::Pink, coord: (2, 0) },
+                             Event::BridgeOpened { color: Color::Pink, coord: (3, 0) }]);
+}
+
+#[test]
+fn stop_and_collision_events() {
+    let map = "x--x--x\n|  |\nx--x--x";
+    let mut cars = vec![Car::new(0, 0, Color::Red), Car::new(0, 1, Color::Blue)];
+    let first = [Some(Dir::Right), None];
+    let (events, result) = tick(map, &mut cars, &[], &first);
+    assert_eq!(result, Ok(()));
+    assert_eq!(events[0], Event::Stopped { car: 1 });
+
+    // Waiting again does not stop the car twice.
+    let (events, _) = tick(map, &mut cars, &first, &first);
+    assert_eq!(events, [Event::Moved { car: 0, from: (1, 0), to: (2, 0), dir: Dir::Right }]);
+
+    let mut cars = vec![Car::new(0, 0, Color::Red), Car::new(2, 0, Color::Blue)];
+    let (events, result) = tick(map, &mut cars, &[], &[Some(Dir::Right), Some(Dir::Left)]);
+    assert_eq!(result, Err((0, MoveError::Collision)));
+    assert_eq!(events, [Event::Collision { car: 0, coord: (1, 0) }]);
+}
+
+#[test]
+fn solutions_move_every_moving_car_and_fill_every_house() {
+    for (n, level, solution) in common::solved() {
+        let ticks = solver::events(&level.map, &level.cars, &solution);
+        assert_eq!(ticks.len(), solution.dirs.len(), "scenario {}", n);
+        for (events, moves) in ticks.iter().zip(solution.dirs.iter()) {
+            let moved = events.iter().filter(|e| matches!(e, Event::Moved { .. })).count();
+            assert_eq!(moved, moves.iter().filter(|dir| dir.is_some()).count(), "scenario {}", n);
+        }
+        let houses = level.map.iter().filter(|e| matches!(e.typ, Type::House(_))).count();
+        let delivered = ticks.iter().flatten().filter(|e| matches!(e, Event::Delivered { .. })).count();
+        assert_eq!(delivered, houses, "scenario {}", n);
+    }
+}
@@ -0,0 +1,62 @@
+//! Run the fuzz targets over their seed corpus and random mutations of it, so
+//! that they are exercised without a fuzzer.
+
+extern crate rgb_express_solver;
+
+use std::fs;
+use std::path;
+use rgb_express_solver::{fuzz, rng};
+
+const MUTATIONS: usize = 200;
+
+fn corpus(target: &str) -> Vec<Vec<u8>> {
+    let dir = path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus").join(target);
+    let mut files: Vec<_> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+    files.sort();
+    files.iter().map(|file| fs::read(file).unwrap()).collect()
+}
+
+/// Flip, insert and remove a few bytes.
+fn mutate(data: &[u8], rng: &mut rng::Rng) -> Vec<u8> {
+    let mut data = data.to_vec();
+    for _ in 0..1 + rng.below(8) {
+        let i = rng.below(data.len() + 1);
+        match rng.below(3) {
+            0 if i < data.len() => data[i] = *rng.pick(b"xOrRgGbByYv^~#pvoc .-|\n:\xff"),
+            1 if i < data.len() => { data.remove(i); },
+            _ => data.insert(i, rng.next_u64() as u8),
+        }
+    }
+    data
+}
+
+fn run(target: &str, f: fn(&[u8])) {
+    let corpus = corpus(target);
+    assert!(!corpus.is_empty());
+    let mut rng = rng::Rng::new(0);
+    for data in corpus.iter() {
+        f(data);
+        for _ in 0..MUTATIONS {
+            f(&mutate(data, &mut rng));
+        }
+    }
+}
+
+#[test]
+fn parser() {
+    run("parser", fuzz::parse);
+}
+
+#[test]
+fn simulator() {
+    run("simulator", fuzz::simulate);
+}
+
+#[test]
+fn parser_rejects_roads_out_of_the_map() {
+    fuzz::parse(b"x--");
+    fuzz::parse(b"x\n|");
+    fuzz::parse(b"x\n   |");
+    fuzz::parse(b"^\n");
+    fuzz::parse(b"");
+}
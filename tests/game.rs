@@ -0,0 +1,58 @@
+extern crate rgb_express_solver;
+
+use rgb_express_solver::elem::{Car, Color, Dir};
+use rgb_express_solver::event::Event;
+use rgb_express_solver::game::Game;
+use rgb_express_solver::map::{Map, MoveError};
+
+fn game() -> Game {
+    Game::new("x--r--x--R".parse::<Map>().unwrap(), vec![Car::new(0, 0, Color::Red)])
+}
+
+const RIGHT: [Option<Dir>; 1] = [Some(Dir::Right)];
+
+#[test]
+fn game_is_won_once_every_house_is_full() {
+    let mut game = game();
+    for tick in 1..=3 {
+        assert!(!game.is_won());
+        assert!(game.step(&RIGHT).is_ok());
+        assert_eq!(game.tick(), tick);
+    }
+    assert!(game.is_won());
+    assert_eq!(game.step(&RIGHT), Err(MoveError::NoRoad));
+    assert_eq!(game.tick(), 3);
+}
+
+#[test]
+fn step_returns_the_events() {
+    let mut game = game();
+    assert_eq!(game.step(&RIGHT), Ok(vec![Event::Moved { car: 0, from: (0, 0), to: (1, 0), dir: Dir::Right },
+                                          Event::PickedUp { car: 0, cube: Color::Red, coord: (1, 0) }]));
+    assert_eq!(game.step(&[None]), Ok(vec![Event::Stopped { car: 0 }]));
+    assert_eq!(game.step(&RIGHT), Err(MoveError::Stopped));
+}
+
+#[test]
+fn undo_and_snapshots() {
+    let mut game = game();
+    let start = game.snapshot();
+    game.step(&RIGHT).unwrap();
+    let cube = game.snapshot();
+    assert_eq!(game.cars()[0].cubes.len(), 1);
+    game.step(&RIGHT).unwrap();
+
+    assert!(game.undo());
+    assert_eq!(game.cars()[0].coord, (1, 0));
+    game.rewind();
+    assert_eq!(game.tick(), 0);
+    assert_eq!(game.map().to_text(), start.map.to_text());
+    assert!(!game.undo());
+
+    game.restore(cube);
+    assert_eq!(game.tick(), 0);
+    assert_eq!(game.cars()[0].cubes.len(), 1);
+    game.step(&RIGHT).unwrap();
+    game.step(&RIGHT).unwrap();
+    assert!(game.is_won());
+}
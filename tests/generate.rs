@@ -0,0 +1,13 @@
+extern crate rgb_express_solver;
+
+use rgb_express_solver::generate::{self, Params};
+
+#[test]
+fn parameters_out_of_range_are_refused() {
+    let small = Params { width: 2, height: 2, ..Params::default() };
+    assert!(generate::generate(&small).is_err());
+    let trucks = Params { trucks: 0, ..Params::default() };
+    assert!(generate::generate(&trucks).is_err());
+    let colors = Params { colors: 5, ..Params::default() };
+    assert!(generate::generate(&colors).is_err());
+}
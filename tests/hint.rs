@@ -0,0 +1,25 @@
+extern crate rgb_express_solver;
+
+use rgb_express_solver::elem::{Car, Color, Dir};
+use rgb_express_solver::hint::{self, Hint};
+use rgb_express_solver::map::Map;
+use rgb_express_solver::scenario;
+
+#[test]
+fn hints_follow_the_chosen_drop_zones() {
+    // A drop zone taking cubes would unload the cube meant for the house.
+    let map = "x--r--O--R".parse::<Map>().unwrap();
+    let cars = [Car::new(0, 0, Color::Red)];
+    let routes = [vec![Dir::Right]];
+    assert!(matches!(hint::hint(&map, &cars, &routes, Some(&[false]), 2, None), Hint::Moves(ref m) if m[0] == [Dir::Right, Dir::Right]));
+    assert!(matches!(hint::hint(&map, &cars, &routes, Some(&[true]), 2, None), Hint::Unsolvable { tick: 0 }));
+    assert!(matches!(hint::hint(&map, &cars, &routes, None, 2, None), Hint::Moves(_)));
+}
+
+#[test]
+fn hints_give_up_past_their_budget() {
+    let level = scenario::all()[2].level();
+    let routes = vec![vec![]; level.cars.len()];
+    assert!(matches!(hint::hint(&level.map, &level.cars, &routes, None, 1, Some(1)), Hint::GaveUp));
+    assert!(matches!(hint::hint(&level.map, &level.cars, &routes, None, 1, None), Hint::Moves(_)));
+}
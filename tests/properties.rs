@@ -0,0 +1,242 @@
+//! Properties of the solver checked over random small maps. A failing case is
+//! shrunk before being reported as a level file.
+
+extern crate rgb_express_solver;
+
+use rgb_express_solver::elem::{Car, Color, Dir, Type};
+use rgb_express_solver::map::Map;
+use rgb_express_solver::game::Snapshot;
+use rgb_express_solver::{level, rng, solver, transform, vec2d};
+
+const CASES: u64 = 300;
+
+#[derive(Clone)]
+struct Case {
+    map: Map,
+    cars: Vec<Car>,
+}
+
+impl Case {
+    fn random(rng: &mut rng::Rng) -> Case {
+        let width = 2 + rng.below(3);
+        let height = 1 + rng.below(3);
+        let mut map: Map = vec2d::Vec2D::new(width, height);
+        let types = [Type::Empty, Type::Road, Type::Road, Type::Road,
+                     Type::Cube(Color::Red), Type::Cube(Color::Green),
+                     Type::House(Color::Red), Type::House(Color::Green),
+                     Type::ArmedButton(Color::Pink), Type::OpenBridge(Color::Pink), Type::ClosedBridge(Color::Pink),
+                     Type::DropOff];
+        for e in map.iter_mut() {
+            e.typ = *rng.pick(&types);
+        }
+        for y in 0..height {
+            for x in 0..width {
+                if x + 1 < width && rng.below(10) < 6 {
+                    connect(&mut map, (x, y), Dir::Right);
+                }
+                if y + 1 < height && rng.below(10) < 6 {
+                    connect(&mut map, (x, y), Dir::Down);
+                }
+            }
+        }
+        let mut cells: Vec<(usize, usize)> = (0..height).flat_map(|y| (0..width).map(move |x| (x, y)))
+                                                        .filter(|&c| map[c].typ != Type::Empty)
+                                                        .collect();
+        rng.shuffle(&mut cells);
+        let cars = cells.iter().take(1 + rng.below(2))
+                        .zip([Color::Red, Color::Blue].iter())
+                        .map(|(&(x, y), &color)| Car::new(x, y, color))
+                        .collect();
+        Case { map, cars }
+    }
+
+    /// Smaller cases: a car less, a cell emptied or made a road, a road cut.
+    fn shrinks(&self) -> Vec<Case> {
+        let mut cases = vec![];
+        if self.cars.len() > 1 {
+            for i in 0..self.cars.len() {
+                let mut case = self.clone();
+                case.cars.remove(i);
+                cases.push(case);
+            }
+        }
+        for y in 0..self.map.height {
+            for x in 0..self.map.width {
+                let typ = self.map[(x, y)].typ;
+                if typ != Type::Empty && !self.cars.iter().any(|car| car.coord == (x, y)) {
+                    let mut case = self.clone();
+                    for &dir in [Dir::Up, Dir::Right, Dir::Down, Dir::Left].iter() {
+                        if case.map[(x, y)].connected(dir) {
+                            disconnect(&mut case.map, (x, y), dir);
+                        }
+                    }
+                    case.map[(x, y)].typ = Type::Empty;
+                    cases.push(case);
+                }
+                if typ != Type::Empty && typ != Type::Road {
+                    let mut case = self.clone();
+                    case.map[(x, y)].typ = Type::Road;
+                    cases.push(case);
+                }
+                for &dir in [Dir::Right, Dir::Down].iter() {
+                    if self.map[(x, y)].connected(dir) {
+                        let mut case = self.clone();
+                        disconnect(&mut case.map, (x, y), dir);
+                        cases.push(case);
+                    }
+                }
+            }
+        }
+        cases
+    }
+
+    fn transform(&self, t: transform::Transform) -> Case {
+        Case {
+            map: t.map(&self.map),
+            cars: t.cars(&self.map, &self.cars).unwrap(),
+        }
+    }
+
+    fn solver(&self) -> solver::Solver {
+        solver::Solver::new(self.map.clone(), self.cars.clone())
+    }
+}
+
+fn connect(map: &mut Map, coord: (usize, usize), dir: Dir) {
+    let next = map.neighbour(coord, dir).unwrap();
+    if map[coord].typ != Type::Empty && map[next].typ != Type::Empty {
+        map[coord].connect(dir);
+        map[next].connect(dir.rev());
+    }
+}
+
+fn disconnect(map: &mut Map, coord: (usize, usize), dir: Dir) {
+    let next = map.neighbour(coord, dir).unwrap();
+    map[coord].disconnect(dir);
+    map[next].disconnect(dir.rev());
+}
+
+/// Check `property` on random cases, shrinking the first failing one.
+fn check(property: &dyn Fn(&Case) -> Result<(), String>) {
+    for seed in 0..CASES {
+        let mut case = Case::random(&mut rng::Rng::new(seed));
+        let mut error = match property(&case) {
+            Ok(()) => continue,
+            Err(error) => error,
+        };
+        'shrink: loop {
+            for smaller in case.shrinks() {
+                if let Err(e) = property(&smaller) {
+                    case = smaller;
+                    error = e;
+                    continue 'shrink;
+                }
+            }
+            break;
+        }
+        panic!("seed {}: {}\n{}", seed, error, level::Level::new(case.map, case.cars));
+    }
+}
+
+/// Whether some moves solve `case`, trying them all without the solver's
+/// search. Moves are still played by `Snapshot::next`, as in the solver.
+fn exhaustive(case: &Case) -> bool {
+    fn search(position: &Snapshot) -> bool {
+        let dirs = [None, Some(Dir::Up), Some(Dir::Right), Some(Dir::Down), Some(Dir::Left)];
+        let combos = (0..position.cars.len()).fold(1, |n, _| n * dirs.len());
+        (1..combos).any(|mut k| {
+            let moves: Vec<_> = position.cars.iter().map(|_| { let d = dirs[k % dirs.len()]; k /= dirs.len(); d }).collect();
+            match position.next(&moves, &mut ()) {
+                Ok(next) => next.map.is_solved() || search(&next),
+                Err(_) => false,
+            }
+        })
+    }
+    let zones = case.map.iter().filter(|e| e.typ == Type::DropOff).count();
+    (0..1 << zones).any(|k: u32| {
+        let mut map = case.map.clone();
+        map.set_targets(&(0..zones).map(|i| k >> i & 1 == 1).collect::<Vec<_>>());
+        search(&Snapshot { map, cars: case.cars.clone(), from: vec![] })
+    })
+}
+
+fn solvable(case: &Case) -> bool {
+    case.solver().solve().is_some()
+}
+
+#[test]
+fn solutions_verify() {
+    check(&|case| {
+        let solution = match case.solver().solve() {
+            Some(solution) => solution,
+            None => return Ok(()),
+        };
+        let frames = solver::simulate(&case.map, &case.cars, &solution);
+        if frames.len() != solution.dirs.len() + 1 {
+            return Err(format!("illegal move at tick {}", frames.len()));
+        }
+        if !frames.last().unwrap().0.is_solved() {
+            return Err("houses left after the solution".to_string());
+        }
+        Ok(())
+    });
+}
+
+#[test]
+fn breadth_and_depth_first_agree() {
+    check(&|case| {
+        let mut bfs = case.solver();
+        bfs.set_strategy(solver::Strategy::BreadthFirst);
+        match (solvable(case), bfs.solve().is_some()) {
+            (dfs, bfs) if dfs == bfs => Ok(()),
+            (dfs, bfs) => Err(format!("depth first: {}, breadth first: {}", dfs, bfs)),
+        }
+    });
+}
+
+#[test]
+fn solvability_is_invariant_under_rotation_and_mirroring() {
+    check(&|case| {
+        let expected = solvable(case);
+        let rotated = case.transform(transform::Transform::Rotate90);
+        let mirrored = case.transform(transform::Transform::MirrorHorizontal);
+        let both = rotated.transform(transform::Transform::MirrorHorizontal);
+        for (name, other) in [("rotated", &rotated), ("mirrored", &mirrored), ("rotated mirrored", &both)] {
+            if solvable(other) != expected {
+                return Err(format!("solvable: {}, {}: {}", expected, name, !expected));
+            }
+        }
+        Ok(())
+    });
+}
+
+/// Both searches play moves with the same engine, so this checks the search
+/// of the solver, not the rules: `rules.rs` and `simultaneous.rs` do.
+#[test]
+fn search_finds_what_brute_force_finds() {
+    check(&|case| {
+        match (solvable(case), exhaustive(case)) {
+            (solver, all) if solver == all => Ok(()),
+            (solver, all) => Err(format!("solver: {}, exhaustive search: {}", solver, all)),
+        }
+    });
+}
+
+#[test]
+fn solutions_survive_reordering_the_cars() {
+    check(&|case| {
+        let mut solution = match case.solver().solve() {
+            Some(solution) => solution,
+            None => return Ok(()),
+        };
+        let mut cars = case.cars.clone();
+        cars.reverse();
+        for moves in solution.dirs.iter_mut() {
+            moves.reverse();
+        }
+        if !solver::verify(&case.map, &cars, &solution) {
+            return Err("the solution fails once the cars are reversed".to_string());
+        }
+        Ok(())
+    });
+}
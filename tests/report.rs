@@ -0,0 +1,11 @@
+extern crate rgb_express_solver;
+
+use rgb_express_solver::report;
+
+#[test]
+fn json_strings_escape_control_characters() {
+    assert_eq!(report::json_string("a\"b\\c"), "\"a\\\"b\\\\c\"");
+    assert_eq!(report::json_string("\n\r\t"), "\"\\n\\r\\t\"");
+    assert_eq!(report::json_string("\u{0}\u{1b}\u{1f} "), "\"\\u0000\\u001b\\u001f \"");
+    assert_eq!(report::json_string("</script>"), "\"\\u003c/script>\"");
+}
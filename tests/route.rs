@@ -0,0 +1,58 @@
+extern crate rgb_express_solver;
+
+use rgb_express_solver::elem::Dir;
+use rgb_express_solver::route::{self, Route};
+use rgb_express_solver::{scenario, solver};
+
+fn route(s: &str) -> Route {
+    s.parse().unwrap()
+}
+
+#[test]
+fn notation() {
+    let expected = Route(vec![Some(Dir::Right), Some(Dir::Right), Some(Dir::Down), Some(Dir::Down), Some(Dir::Down), None]);
+    for s in ["2R 3D 1.", "2R3D.", "RRDDD.", "2→ 3↓ .", "  R 1R  DDD  . "] {
+        assert_eq!(route(s), expected, "{:?}", s);
+    }
+    assert_eq!(expected.to_string(), "2R 3D 1.");
+    assert_eq!(format!("{:#}", expected), "2→ 3↓ 1.");
+    assert_eq!(route("12L").0.len(), 12);
+    assert_eq!(route(""), Route::default());
+    assert_eq!(Route::default().to_string(), "");
+}
+
+#[test]
+fn errors() {
+    let error = |s: &str| s.parse::<Route>().unwrap_err();
+    assert_eq!(error("2R x"), "unknown move `x` at column 4");
+    assert_eq!(error("2 R"), "count without a move at column 2");
+    assert_eq!(error("0R"), "zero count at column 2");
+    assert_eq!(error("2R 3"), "count without a move at the end");
+    assert_eq!(error("99999999999999999999R"), "count too large at column 5");
+    assert_eq!(error("9999999999999R"), "count too large at column 5");
+    assert_eq!(error("10001R"), "count too large at column 5");
+    assert_eq!(route("10000R").0.len(), route::MAX_COUNT);
+}
+
+#[test]
+fn moves_stop_at_the_first_wait() {
+    assert_eq!(route("2R 2.").moves(), Some(vec![Dir::Right, Dir::Right]));
+    assert_eq!(route("R . R").moves(), None);
+}
+
+#[test]
+fn solutions_read_back_from_their_routes() {
+    for scenario in scenario::all().into_iter().take(10) {
+        let level = scenario.level();
+        let solution = match solver::Solver::new(level.map.clone(), level.cars.clone()).solve() {
+            Some(solution) => solution,
+            None => continue,
+        };
+        let routes: Vec<Route> = (0..level.cars.len()).map(|i| Route::of(&solution, i)).collect();
+        for r in routes.iter() {
+            assert_eq!(&route(&r.to_string()), r);
+            assert_eq!(&route(&format!("{:#}", r)), r);
+        }
+        assert_eq!(route::solution(&routes, solution.targets.clone()), solution);
+    }
+}
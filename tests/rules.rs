@@ -0,0 +1,142 @@
+extern crate rgb_express_solver;
+
+use rgb_express_solver::elem::{Car, Color, Dir, Type};
+use rgb_express_solver::map::{Map, MoveError};
+use rgb_express_solver::game::Game;
+
+fn red_truck() -> Car {
+    Car::new(0, 0, Color::Red)
+}
+
+#[test]
+fn pickup_stacks_the_cube() {
+    let mut map = "x--r--x".parse::<Map>().unwrap();
+    let mut car = red_truck();
+    assert_eq!(map.move_car(&mut car, Some(Dir::Right)), Ok(()));
+    assert_eq!(car.cubes, vec![Type::Cube(Color::Red)]);
+    assert_eq!(map[(1, 0)].typ, Type::Road);
+}
+
+#[test]
+fn pickup_at_capacity_is_refused() {
+    let mut map = "x--g--x".parse::<Map>().unwrap();
+    let mut car = red_truck();
+    car.cubes = vec![Type::Cube(Color::Red); 3];
+    assert_eq!(map.move_car(&mut car, Some(Dir::Right)), Err(MoveError::Overloaded));
+}
+
+#[test]
+fn delivery_fills_the_house() {
+    let mut map = "x--R--x".parse::<Map>().unwrap();
+    let mut car = red_truck();
+    car.cubes = vec![Type::Cube(Color::Blue), Type::Cube(Color::Red)];
+    assert_eq!(map.move_car(&mut car, Some(Dir::Right)), Ok(()));
+    assert_eq!(car.cubes, vec![Type::Cube(Color::Blue)]);
+    assert_eq!(map[(1, 0)].typ, Type::FullHouse(Color::Red));
+}
+
+#[test]
+fn wrong_color_house_is_refused() {
+    let mut map = "x--B--x".parse::<Map>().unwrap();
+    let mut car = red_truck();
+    car.cubes = vec![Type::Cube(Color::Blue), Type::Cube(Color::Red)];
+    assert_eq!(map.move_car(&mut car, Some(Dir::Right)), Err(MoveError::WrongColor));
+}
+
+#[test]
+fn empty_truck_cannot_cross_a_house() {
+    let mut map = "x--R--x".parse::<Map>().unwrap();
+    let mut car = red_truck();
+    assert_eq!(map.move_car(&mut car, Some(Dir::Right)), Err(MoveError::EmptyTruck));
+}
+
+#[test]
+fn full_house_blocks() {
+    let mut map = "x--R--x".parse::<Map>().unwrap();
+    map[(1, 0)].typ = Type::FullHouse(Color::Red);
+    let mut car = red_truck();
+    car.cubes = vec![Type::Cube(Color::Red)];
+    assert_eq!(map.move_car(&mut car, Some(Dir::Right)), Err(MoveError::FullHouse));
+}
+
+#[test]
+fn roads_are_used_once() {
+    let mut map = "x--x--x".parse::<Map>().unwrap();
+    let mut car = red_truck();
+    assert_eq!(map.move_car(&mut car, Some(Dir::Right)), Ok(()));
+    assert_eq!(map.move_car(&mut car, Some(Dir::Left)), Err(MoveError::NoRoad));
+}
+
+#[test]
+fn road_off_the_map_is_refused() {
+    let mut map = "x--x".parse::<Map>().unwrap();
+    map[(0, 0)].connect(Dir::Left);
+    map[(1, 0)].connect(Dir::Right);
+    let mut car = red_truck();
+    assert_eq!(map.move_car(&mut car, Some(Dir::Left)), Err(MoveError::OffMap));
+    let mut game = Game::new(map, vec![red_truck(), Car::new(1, 0, Color::Blue)]);
+    assert_eq!(game.step_events(&[None, Some(Dir::Right)], &mut ()), Err((1, MoveError::OffMap)));
+}
+
+#[test]
+fn armed_button_toggles_every_bridge_of_its_color() {
+    let mut map = "x--^p-~p-#p-~v-^p".parse::<Map>().unwrap();
+    let mut car = red_truck();
+    assert_eq!(map.move_car(&mut car, Some(Dir::Right)), Ok(()));
+    assert_eq!(map[(1, 0)].typ, Type::PushedButton(Color::Pink));
+    assert_eq!(map[(2, 0)].typ, Type::ClosedBridge(Color::Pink));
+    assert_eq!(map[(3, 0)].typ, Type::OpenBridge(Color::Pink));
+    assert_eq!(map[(4, 0)].typ, Type::OpenBridge(Color::Violet));
+    assert_eq!(map[(5, 0)].typ, Type::PushedButton(Color::Pink));
+    // The bridge is now closed and can be crossed.
+    assert_eq!(map.move_car(&mut car, Some(Dir::Right)), Ok(()));
+}
+
+#[test]
+fn open_bridge_is_refused() {
+    let mut map = "x--~p-x".parse::<Map>().unwrap();
+    let mut car = red_truck();
+    assert_eq!(map.move_car(&mut car, Some(Dir::Right)), Err(MoveError::OpenBridge));
+}
+
+#[test]
+fn truck_on_an_opening_bridge_is_stranded() {
+    // The red truck drives onto the closed bridge while the blue one presses
+    // the button that opens it.
+    let map = "x--#p-x\n \nx--^p-x".parse::<Map>().unwrap();
+    let mut game = Game::new(map, vec![red_truck(), Car::new(0, 1, Color::Blue)]);
+    let moves = [Some(Dir::Right), Some(Dir::Right)];
+    assert_eq!(game.step_events(&moves, &mut ()), Err((0, MoveError::Stranded)));
+    assert_eq!(game.tick(), 0);
+}
+
+#[test]
+fn stopped_truck_cannot_move_again() {
+    let map = "x--x--x\n \nx--x--x".parse::<Map>().unwrap();
+    let mut game = Game::new(map, vec![red_truck(), Car::new(0, 1, Color::Blue)]);
+    let first = [Some(Dir::Right), None];
+    assert!(game.step(&first).is_ok());
+    let second = [Some(Dir::Right), Some(Dir::Right)];
+    assert_eq!(game.step_events(&second, &mut ()), Err((1, MoveError::Stopped)));
+}
+
+#[test]
+fn drop_zone_target_unloads_the_top_cube() {
+    let mut map = "x--O--x".parse::<Map>().unwrap();
+    map.set_targets(&[true]);
+    let mut car = red_truck();
+    car.cubes = vec![Type::Cube(Color::Blue), Type::Cube(Color::Red)];
+    assert_eq!(map.move_car(&mut car, Some(Dir::Right)), Ok(()));
+    assert_eq!(car.cubes, vec![Type::Cube(Color::Blue)]);
+    assert_eq!(map[(1, 0)].typ, Type::Cube(Color::Red));
+}
+
+#[test]
+fn drop_zone_off_target_keeps_the_cargo() {
+    let mut map = "x--O--x".parse::<Map>().unwrap();
+    let mut car = red_truck();
+    car.cubes = vec![Type::Cube(Color::Red)];
+    assert_eq!(map.move_car(&mut car, Some(Dir::Right)), Ok(()));
+    assert_eq!(car.cubes, vec![Type::Cube(Color::Red)]);
+    assert_eq!(map[(1, 0)].typ, Type::DropOff);
+}
@@ -0,0 +1,70 @@
+extern crate rgb_express_solver;
+
+mod common;
+
+use rgb_express_solver::{level, scenario, solver};
+
+/// Scenarios the solver proves unsolvable.
+const UNSOLVABLE: [usize; 2] = [5, 14];
+
+#[test]
+fn every_scenario_is_solved_and_replays() {
+    for (n, scenario) in scenario::all().into_iter().enumerate() {
+        if common::TOO_SLOW.contains(&n) {
+            continue;
+        }
+        let level = scenario.level();
+        let mut s = solver::Solver::new(level.map.clone(), level.cars.clone());
+        s.set_budget(1_000_000);
+        let solution = s.solve();
+        assert!(!s.exhausted(), "scenario {}: budget exhausted", n);
+
+        let solution = match solution {
+            Some(solution) => solution,
+            None => {
+                assert!(UNSOLVABLE.contains(&n), "scenario {}: no solution found", n);
+                continue;
+            },
+        };
+        assert!(!UNSOLVABLE.contains(&n), "scenario {}: unexpected solution", n);
+
+        let frames = solver::simulate(&level.map, &level.cars, &solution);
+        assert_eq!(frames.len(), solution.dirs.len() + 1, "scenario {}: illegal move", n);
+        assert!(frames.last().unwrap().0.is_solved(), "scenario {}: houses left", n);
+    }
+}
+
+#[test]
+fn breadth_first_finds_no_longer_solutions() {
+    for (n, level, dfs) in common::solved() {
+        let mut bfs = solver::Solver::new(level.map.clone(), level.cars.clone());
+        bfs.set_strategy(solver::Strategy::BreadthFirst);
+        bfs.set_budget(1_000_000);
+        let bfs = bfs.solve().unwrap();
+
+        let frames = solver::simulate(&level.map, &level.cars, &bfs);
+        assert_eq!(frames.len(), bfs.dirs.len() + 1, "scenario {}: illegal move", n);
+        assert!(frames.last().unwrap().0.is_solved(), "scenario {}: houses left", n);
+        assert!(bfs.dirs.len() <= dfs.dirs.len(), "scenario {}", n);
+    }
+}
+
+#[test]
+fn a_level_without_trucks_has_no_solution() {
+    let level: level::Level = "map:\nx--R\n".parse().unwrap();
+    for strategy in [solver::Strategy::DepthFirst, solver::Strategy::BreadthFirst] {
+        let mut s = solver::Solver::new(level.map.clone(), level.cars.clone());
+        s.set_strategy(strategy);
+        assert!(s.solve().is_none(), "{:?}", strategy);
+    }
+}
+
+#[test]
+fn many_drop_zones_are_tried_one_choice_at_a_time() {
+    let map = format!("x--r--R\n \n{}", "O  ".repeat(70));
+    let level: level::Level = format!("car: 0 0 red\nmap:\n{}\n", map).parse().unwrap();
+    let mut s = solver::Solver::new(level.map.clone(), level.cars.clone());
+    s.set_budget(1_000);
+    let solution = s.solve().unwrap();
+    assert_eq!(solution.targets, vec![false; 70]);
+}
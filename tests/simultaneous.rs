@@ -0,0 +1,72 @@
+extern crate rgb_express_solver;
+
+use rgb_express_solver::elem::{Car, Color, Dir, Type};
+use rgb_express_solver::game::Game;
+use rgb_express_solver::map::{Map, MoveError};
+
+/// Play a tick on `map`, the cars listed in both orders, and check they get
+/// the same outcome.
+fn tick(map: &str, cars: &[Car], moves: &[Option<Dir>]) -> Result<(Map, Vec<Car>), MoveError> {
+    let play = |cars: &[Car], moves: &[Option<Dir>]| {
+        let mut game = Game::new(map.parse::<Map>().unwrap(), cars.to_vec());
+        game.step(moves).map(|_| (game.map().clone(), game.cars().to_vec()))
+    };
+    let forward = play(cars, moves);
+    let reversed: Vec<Car> = cars.iter().rev().cloned().collect();
+    let reversed_moves: Vec<Option<Dir>> = moves.iter().rev().cloned().collect();
+    let backward = play(&reversed, &reversed_moves).map(|(map, mut cars)| {
+        cars.reverse();
+        (map, cars)
+    });
+    let outcome = |result: &Result<(Map, Vec<Car>), MoveError>| result.as_ref().map(|(map, cars)| {
+        (map.to_string(), cars.iter().map(|car| (car.coord, car.cubes.clone())).collect::<Vec<_>>())
+    }).map_err(|e| *e);
+    assert_eq!(outcome(&forward), outcome(&backward));
+    forward
+}
+
+fn coords(cars: &[Car]) -> Vec<(usize, usize)> {
+    cars.iter().map(|car| car.coord).collect()
+}
+
+#[test]
+fn a_car_follows_another_one() {
+    let cars = [Car::new(0, 0, Color::Red), Car::new(1, 0, Color::Blue)];
+    let (_, cars) = tick("x--x--x", &cars, &[Some(Dir::Right), Some(Dir::Right)]).unwrap();
+    assert_eq!(coords(&cars), [(1, 0), (2, 0)]);
+}
+
+#[test]
+fn cars_entering_the_same_cell_collide() {
+    let cars = [Car::new(0, 0, Color::Red), Car::new(2, 0, Color::Blue)];
+    assert_eq!(tick("x--x--x", &cars, &[Some(Dir::Right), Some(Dir::Left)]).err(), Some(MoveError::Collision));
+}
+
+#[test]
+fn cars_swapping_cells_collide() {
+    let cars = [Car::new(0, 0, Color::Red), Car::new(1, 0, Color::Blue)];
+    assert_eq!(tick("x--x--x", &cars, &[Some(Dir::Right), Some(Dir::Left)]).err(), Some(MoveError::Collision));
+}
+
+#[test]
+fn a_waiting_car_is_run_into() {
+    let cars = [Car::new(0, 0, Color::Red), Car::new(1, 0, Color::Blue)];
+    assert_eq!(tick("x--x--x", &cars, &[Some(Dir::Right), None]).err(), Some(MoveError::Collision));
+}
+
+#[test]
+fn bridges_switch_once_every_car_moved() {
+    let map = "x--^p\n.\nx--~p";
+    let cars = [Car::new(0, 0, Color::Red), Car::new(0, 1, Color::Blue)];
+    let moves = [Some(Dir::Right), Some(Dir::Right)];
+    assert_eq!(tick(map, &cars, &moves).err(), Some(MoveError::OpenBridge));
+
+    let map = "x--^p\n.\nx--#p";
+    assert_eq!(tick(map, &cars, &moves).err(), Some(MoveError::Stranded));
+
+    // A color is pressed once, however many of its buttons are run over.
+    let map = "x--^p-#p\n.\nx--^p";
+    let (map, _) = tick(map, &cars, &moves).unwrap();
+    assert_eq!(map[(2, 0)].typ, Type::OpenBridge(Color::Pink));
+    assert_eq!((map[(1, 0)].typ, map[(1, 1)].typ), (Type::PushedButton(Color::Pink), Type::PushedButton(Color::Pink)));
+}
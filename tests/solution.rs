@@ -0,0 +1,69 @@
+extern crate rgb_express_solver;
+
+mod common;
+
+use rgb_express_solver::solution::SolutionFile;
+use rgb_express_solver::transform::Transform;
+use rgb_express_solver::{scenario, solver};
+
+#[test]
+fn solutions_round_trip_and_verify() {
+    for (n, level, solution) in common::solved() {
+        let text = SolutionFile::new(&level.map, &level.cars, solution.clone()).to_string();
+        let saved: SolutionFile = text.parse().unwrap();
+        assert_eq!(saved.solution, solution, "scenario {}", n);
+        assert_eq!(saved.to_string(), text);
+        assert_eq!(saved.verify(&level.map, &level.cars), Ok(()), "scenario {}", n);
+    }
+}
+
+#[test]
+fn format() {
+    let level = scenario::all()[4].level();
+    let saved: SolutionFile = "# found by hand\nlevel: bdb938138465cde8\nlayout: 53fb6c4f68da06c2\nruleset: 2\ntargets:\ncar: RDDL...\ncar: LDDLULL\n".parse().unwrap();
+    assert_eq!(saved.solution.dirs.len(), 7);
+    assert_eq!(saved.solution.dirs[4], [None, Some(rgb_express_solver::elem::Dir::Up)]);
+    assert_eq!(saved.verify(&level.map, &level.cars), Ok(()));
+    assert_eq!(saved.to_string(), "level: bdb938138465cde8\nlayout: 53fb6c4f68da06c2\nruleset: 2\ntargets:\ncar: RDDL...\ncar: LDDLULL\n");
+}
+
+#[test]
+fn errors() {
+    let error = |s: &str| s.parse::<SolutionFile>().err().map(|e| e.to_string());
+    assert_eq!(error("level: 1\nruleset: 2\ntargets:\ncar: RX\n"), Some("line 4: invalid move `X`".to_string()));
+    assert_eq!(error("level: 1\nruleset: 2\ntargets:\ncar: RR\ncar: R\n"), Some("line 5: expected 2 moves".to_string()));
+    assert_eq!(error("level: 1\ntargets: 2\n"), Some("line 2: invalid target `2`".to_string()));
+    assert_eq!(error("ruleset: 2\ntargets:\n"), Some("line 2: missing `level:`".to_string()));
+    assert_eq!(error("level: 1\nruleset: 2\ntargets:\n"), Some("line 3: missing `layout:`".to_string()));
+
+    let level = scenario::all()[4].level();
+    let check = |s: &str| s.parse::<SolutionFile>().unwrap().verify(&level.map, &level.cars);
+    assert_eq!(check("level: 1\nlayout: 1\nruleset: 2\ntargets:\ncar: R\ncar: L\n"),
+               Err("solution of level 0000000000000001, not bdb938138465cde8".to_string()));
+    assert_eq!(check("level: bdb938138465cde8\nlayout: 1\nruleset: 2\ntargets:\ncar: RDDL...\ncar: LDDLULL\n"),
+               Err("solution of a rotated, mirrored or reordered copy of the level".to_string()));
+    assert_eq!(check("level: bdb938138465cde8\nlayout: 53fb6c4f68da06c2\nruleset: 1\ntargets:\ncar: RDDL...\ncar: LDDLULL\n"),
+               Err(format!("solution under ruleset 1, not {}", solver::RULESET)));
+    assert_eq!(check("level: bdb938138465cde8\nlayout: 53fb6c4f68da06c2\nruleset: 2\ntargets:\ncar: RDD\n"), Err("moves for 1 car(s), not 2".to_string()));
+    assert_eq!(check("level: bdb938138465cde8\nlayout: 53fb6c4f68da06c2\nruleset: 2\ntargets:\ncar: RDDL..\ncar: LDDLUL\n"),
+               Err("houses left after the last tick".to_string()));
+    assert_eq!(check("level: bdb938138465cde8\nlayout: 53fb6c4f68da06c2\nruleset: 2\ntargets:\ncar: U\ncar: L\n"), Err("illegal move at tick 1".to_string()));
+}
+
+#[test]
+fn copies_taking_other_moves_are_told_apart() {
+    let level = scenario::all()[4].level();
+    let solution = solver::Solver::new(level.map.clone(), level.cars.clone()).solve().unwrap();
+    let saved = SolutionFile::new(&level.map, &level.cars, solution);
+    let copy = "solution of a rotated, mirrored or reordered copy of the level".to_string();
+    for t in [Transform::Rotate90, Transform::MirrorHorizontal] {
+        let (map, cars) = (t.map(&level.map), t.cars(&level.map, &level.cars).unwrap());
+        assert_eq!(saved.verify(&map, &cars), Err(copy.clone()), "{:?}", t);
+    }
+    let mut cars = level.cars.clone();
+    cars.reverse();
+    assert_eq!(saved.verify(&level.map, &cars), Err(copy));
+
+    let (map, cars) = (Transform::Pad(2).map(&level.map), Transform::Pad(2).cars(&level.map, &level.cars).unwrap());
+    assert_eq!(saved.verify(&map, &cars), Ok(()));
+}
@@ -0,0 +1,55 @@
+extern crate rgb_express_solver;
+
+mod common;
+
+use rgb_express_solver::elem::{Car, Color, Dir, Type};
+use rgb_express_solver::map::Map;
+use rgb_express_solver::solver;
+
+#[test]
+fn changes_replay_the_solution() {
+    for (n, level, solution) in common::solved() {
+        let timeline = solution.timeline(&level.map, &level.cars);
+        let frames = solver::simulate(&level.map, &level.cars, &solution);
+        assert_eq!(timeline.len(), solution.dirs.len() + 1, "scenario {}", n);
+
+        let mut map = frames[0].0.clone();
+        for (tick, (state, frame)) in timeline.iter().zip(frames.iter()).enumerate() {
+            for &(coord, typ) in state.changes.iter() {
+                map[coord].typ = typ;
+            }
+            let types = |map: &Map| map.iter().map(|e| e.typ).collect::<Vec<_>>();
+            assert_eq!(types(&map), types(&frame.0), "scenario {}, tick {}", n, tick);
+            for (car, expected) in state.cars.iter().zip(frame.1.iter()) {
+                assert_eq!((car.coord, &car.cubes), (expected.coord, &expected.cubes), "scenario {}, tick {}", n, tick);
+            }
+        }
+    }
+}
+
+#[test]
+fn cargo_and_switches() {
+    let map = "x--r--^p-~p".parse::<Map>().unwrap();
+    let cars = [Car::new(0, 0, Color::Red)];
+    let solution = solver::Solution { dirs: vec![vec![Some(Dir::Right)], vec![Some(Dir::Right)]], targets: vec![] };
+    let timeline = solution.timeline(&map, &cars);
+    assert_eq!(timeline.len(), 3);
+
+    assert!(timeline[0].changes.is_empty());
+    assert_eq!(timeline[0].switches, [((2, 0), Type::ArmedButton(Color::Pink)), ((3, 0), Type::OpenBridge(Color::Pink))]);
+
+    assert_eq!(timeline[1].cars[0].coord, (1, 0));
+    assert_eq!(timeline[1].cars[0].cubes, [Type::Cube(Color::Red)]);
+    assert_eq!(timeline[1].changes, [((1, 0), Type::Road)]);
+
+    assert_eq!(timeline[2].switches, [((2, 0), Type::PushedButton(Color::Pink)), ((3, 0), Type::ClosedBridge(Color::Pink))]);
+    assert_eq!(timeline[2].changes, timeline[2].switches);
+}
+
+#[test]
+fn illegal_moves_end_the_timeline() {
+    let map = "x--x".parse::<Map>().unwrap();
+    let cars = [Car::new(0, 0, Color::Red)];
+    let solution = solver::Solution { dirs: vec![vec![Some(Dir::Right)], vec![Some(Dir::Right)]], targets: vec![] };
+    assert_eq!(solution.timeline(&map, &cars).len(), 2);
+}
@@ -0,0 +1,64 @@
+extern crate rgb_express_solver;
+
+mod common;
+
+use rgb_express_solver::elem::Dir;
+use rgb_express_solver::map::Map;
+use rgb_express_solver::transform::{Rect, Transform};
+use rgb_express_solver::{scenario, solver};
+
+const SYMMETRIES: [Transform; 4] = [Transform::Rotate90, Transform::MirrorHorizontal, Transform::MirrorVertical, Transform::Pad(2)];
+
+#[test]
+fn solutions_follow_the_map() {
+    for (n, level, solution) in common::solved() {
+        for t in SYMMETRIES.iter() {
+            let map = t.map(&level.map);
+            let cars = t.cars(&level.map, &level.cars).unwrap();
+            let solution = t.solution(&level.map, &solution);
+            let frames = solver::simulate(&map, &cars, &solution);
+            assert_eq!(frames.len(), solution.dirs.len() + 1, "scenario {}, {:?}: illegal move", n, t);
+            assert!(frames.last().unwrap().0.is_solved(), "scenario {}, {:?}: houses left", n, t);
+        }
+    }
+}
+
+#[test]
+fn four_quarter_turns_and_two_mirrors_are_identity() {
+    for scenario in scenario::all() {
+        let map = scenario.level().map;
+        assert_eq!(map.rotate90().rotate90().rotate90().rotate90().to_text(), map.to_text());
+        assert_eq!(map.mirror_horizontal().mirror_horizontal().to_text(), map.to_text());
+        assert_eq!(map.mirror_vertical().mirror_vertical().to_text(), map.to_text());
+        assert_eq!(map.rotate90().rotate90().to_text(), map.mirror_horizontal().mirror_vertical().to_text());
+    }
+}
+
+#[test]
+fn rotation_turns_the_roads() {
+    let map: Map = "x--r".parse().unwrap();
+    let rotated = map.rotate90();
+    assert_eq!((rotated.width, rotated.height), (1, 2));
+    assert!(rotated[(0, 0)].connected(Dir::Down));
+    assert!(rotated[(0, 1)].connected(Dir::Up));
+    assert_eq!(rotated.to_text(), "x \n| \nr \n");
+}
+
+#[test]
+fn crop_cuts_the_roads_leaving_it() {
+    let map: Map = "x--x--x\n|     |\nx--x--x".parse().unwrap();
+    let cropped = map.crop(Rect { x: 1, y: 0, width: 2, height: 2 });
+    assert_eq!(cropped.to_text(), "x--x \n   | \nx--x \n");
+    assert!(!cropped[(0, 0)].connected(Dir::Left));
+    let car = rgb_express_solver::elem::Car::new(0, 1, rgb_express_solver::elem::Color::Red);
+    assert!(Transform::Crop(Rect { x: 1, y: 0, width: 2, height: 2 }).cars(&map, &[car]).is_none());
+}
+
+#[test]
+fn pad_surrounds_with_empty_cells() {
+    let map: Map = "x--r".parse().unwrap();
+    let padded = map.pad(1);
+    assert_eq!((padded.width, padded.height), (4, 3));
+    assert!(padded[(1, 1)].connected(Dir::Right));
+    assert_eq!(padded.crop(Rect { x: 1, y: 1, width: 2, height: 1 }).to_text(), map.to_text());
+}